@@ -0,0 +1,132 @@
+//! Derive macros for `frankencell`. Use them through `frankencell` with the `derive` feature
+//! rather than depending on this crate directly.
+
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::{format_ident, quote};
+use syn::{parse_macro_input, Data, DeriveInput, Error, Fields, GenericArgument, PathArguments, Type};
+
+/// Generates a constructor and token-taking accessors for a struct whose fields are
+/// `frankencell::Cell`s, so the `const ID: usize` plumbing doesn't have to be written by hand.
+///
+/// For a struct with a `field: Cell<T, ID>`, this generates:
+/// - `const fn new(...) -> Self`, taking every field in order, with `Cell` fields taken unwrapped
+/// - `fn field<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T`
+/// - `fn field_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut T`
+///
+/// `new` has the struct's visibility, and each accessor has its field's, so private cells stay
+/// private. Fields that aren't cells are passed to `new` as they are, and get no accessors.
+///
+/// # Example
+/// ```rust
+/// use frankencell::{first, Cell, Tokened};
+///
+/// #[derive(Tokened)]
+/// struct Player<const ID: usize> {
+///     id: u64,
+///     name: Cell<String, ID>,
+///     hp: Cell<u32, ID>,
+/// }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let player = Player::new(7, String::from("ferris"), 10);
+///
+/// *player.hp_mut(&mut token) -= 3;
+/// assert_eq!(player.name(&token), "ferris");
+/// assert_eq!((player.id, *player.hp(&token)), (7, 7));
+/// ```
+///
+/// Outside the struct's module, a private cell's accessors can't be called:
+/// ```compile_fail
+/// # use frankencell::first;
+/// mod game {
+///     use frankencell::{Cell, Tokened};
+///
+///     #[derive(Tokened)]
+///     pub struct Player<const ID: usize> {
+///         pub name: Cell<String, ID>,
+///         hp: Cell<u32, ID>,
+///     }
+/// }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let player = game::Player::new(String::from("ferris"), 10);
+/// player.name_mut(&mut token).push('!');
+/// *player.hp_mut(&mut token) = 0;
+/// ```
+#[proc_macro_derive(Tokened)]
+pub fn derive_tokened(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+    tokened(input).unwrap_or_else(Error::into_compile_error).into()
+}
+
+// The value type and family of a `Cell<T, ID>`, matched by the last path segment
+fn cell_type(ty: &Type) -> Option<(&Type, &GenericArgument)> {
+    let Type::Path(path) = ty else {return None};
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Cell" {
+        return None;
+    }
+
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {return None};
+    match args.args.iter().collect::<Vec<_>>()[..] {
+        [GenericArgument::Type(value), family] => Some((value, family)),
+        _ => None,
+    }
+}
+
+fn tokened(input: DeriveInput) -> syn::Result<proc_macro2::TokenStream> {
+    let Data::Struct(data) = &input.data else {
+        return Err(Error::new(Span::call_site(), "Tokened can only be derived for structs"));
+    };
+    let Fields::Named(fields) = &data.fields else {
+        return Err(Error::new(Span::call_site(), "Tokened can only be derived for structs with named fields"));
+    };
+
+    let name = &input.ident;
+    let vis = &input.vis;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let mut params = Vec::new();
+    let mut inits = Vec::new();
+    let mut accessors = Vec::new();
+
+    for field in &fields.named {
+        let ident = field.ident.as_ref().unwrap();
+        let vis = &field.vis;
+
+        match cell_type(&field.ty) {
+            Some((value, family)) => {
+                let ident_mut = format_ident!("{}_mut", ident);
+                params.push(quote!(#ident: #value));
+                inits.push(quote!(#ident: ::frankencell::Cell::new(#ident)));
+                accessors.push(quote! {
+                    #vis fn #ident<'a, U>(&'a self, token: &'a ::frankencell::TokenWith<U, #family>) -> &'a #value {
+                        self.#ident.borrow(token)
+                    }
+
+                    #[allow(clippy::mut_from_ref)]
+                    #vis fn #ident_mut<'a, U>(&'a self, token: &'a mut ::frankencell::TokenWith<U, #family>) -> &'a mut #value {
+                        self.#ident.borrow_mut(token)
+                    }
+                });
+            }
+            None => {
+                let ty = &field.ty;
+                params.push(quote!(#ident: #ty));
+                inits.push(quote!(#ident));
+            }
+        }
+    }
+
+    Ok(quote! {
+        impl #impl_generics #name #ty_generics #where_clause {
+            #[allow(clippy::too_many_arguments)]
+            #vis const fn new(#(#params),*) -> Self {
+                Self { #(#inits),* }
+            }
+
+            #(#accessors)*
+        }
+    })
+}
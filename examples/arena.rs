@@ -50,6 +50,7 @@ impl<T, const ID: usize> Arena<T, ID> {
         unsafe {inner.get_unchecked(index.0)}
     }
 
+    #[allow(clippy::mut_from_ref)]
     fn get_mut(&self, index: &mut Index<ID>) -> &mut T {
         let inner: &mut Vec<T> = unsafe {self.inner.get().as_mut().unwrap()};
 
@@ -85,8 +86,7 @@ fn main() {
     let a = chars.get_mut(&mut a);
     let b = chars.get(&b);
 
-    drop(b);
-    drop(a);
+    println!("{}{}", a, b);
 
     // Under the hood, an `Index` is just a usize. The following:
         // chars.get(&c);
@@ -0,0 +1,75 @@
+/*
+ * C API for frankencell's token-gated byte buffers, built with the `ffi` feature.
+ *
+ * Families are checked at runtime: every token carries a family number, every cell remembers the
+ * family it belongs to, and borrowing fails (returns NULL) if the two don't match or if the
+ * borrow would alias a mutable one. Pointers passed in must either be NULL (where documented) or
+ * have been returned by this API and not yet freed.
+ *
+ * Borrows are counted atomically, so tokens and cells can be used from several threads at once,
+ * but a cell must not be freed while another thread may still use it.
+ */
+
+#ifndef FRANKENCELL_H
+#define FRANKENCELL_H
+
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+
+#ifdef __cplusplus
+extern "C" {
+#endif
+
+/* An opaque handle proving ownership of a family. */
+typedef struct FcToken FcToken;
+
+/* An opaque, token-gated byte buffer. */
+typedef struct FcCell FcCell;
+
+/*
+ * Creates a token for a new family. Returns NULL if every family number has been used.
+ *
+ * Families are numbered down from SIZE_MAX, and no other token of a family created here may ever
+ * exist, including tokens created on the Rust side (from `first()` or otherwise) with the same
+ * ID. The result must be freed with fc_token_free.
+ */
+FcToken *fc_token_new(void);
+
+/* Frees a token. `token` must be NULL or come from fc_token_new. */
+void fc_token_free(FcToken *token);
+
+/*
+ * Creates a cell in `token`'s family holding a copy of `len` bytes from `data`. Returns NULL if
+ * `token` is NULL. `data` must be valid for reads of `len` bytes, or NULL if `len` is 0.
+ */
+FcCell *fc_cell_new(const FcToken *token, const uint8_t *data, size_t len);
+
+/*
+ * Frees a cell. Returns false (and does nothing) if the cell is still borrowed. No other thread
+ * may use the cell concurrently.
+ */
+bool fc_cell_free(FcCell *cell);
+
+/*
+ * Borrows the cell's contents, writing their length to `len`. Returns NULL if `token` belongs to
+ * a different family or the cell is mutably borrowed. The result is only valid until the
+ * matching fc_cell_release.
+ */
+const uint8_t *fc_cell_borrow(FcCell *cell, const FcToken *token, size_t *len);
+
+/*
+ * Mutably borrows the cell's contents, writing their length to `len`. Returns NULL if `token`
+ * belongs to a different family or the cell is already borrowed. The result is only valid until
+ * the matching fc_cell_release.
+ */
+uint8_t *fc_cell_borrow_mut(FcCell *cell, FcToken *token, size_t *len);
+
+/* Ends the mutable borrow, or one of the shared borrows, of a cell. */
+void fc_cell_release(FcCell *cell);
+
+#ifdef __cplusplus
+}
+#endif
+
+#endif /* FRANKENCELL_H */
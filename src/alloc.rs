@@ -0,0 +1,29 @@
+//! The allocator parameter of [TokenBox](crate::boxed::TokenBox) and [Arena](crate::arena::Arena).
+//!
+//! With the `allocator-api` feature, these are `std::alloc`'s unstable `Allocator` and `Global`,
+//! and both types gain `new_in` constructors. Without it, they're stand-ins whose only
+//! implementor is [Global], so the crate doesn't need `#![feature(allocator_api)]`.
+
+#[cfg(feature = "allocator-api")]
+pub use std::alloc::{Allocator, Global};
+
+#[cfg(not(feature = "allocator-api"))]
+pub use stand_in::{Allocator, Global};
+
+#[cfg(not(feature = "allocator-api"))]
+mod stand_in {
+    /// Only implemented by [Global]. Enable the `allocator-api` feature to use other allocators.
+    pub trait Allocator: sealed::Sealed {}
+
+    /// The global allocator.
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct Global;
+
+    impl Allocator for Global {}
+
+    mod sealed {
+        pub trait Sealed {}
+
+        impl Sealed for super::Global {}
+    }
+}
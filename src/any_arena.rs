@@ -0,0 +1,85 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use crate::arena::{Arena, Index};
+use crate::tokens::TokenWith;
+
+/// An arena that can hold values of any (`'static`) type, so a family doesn't need a separate
+/// [Arena] per element type. Internally, there is one `Arena` per type, and since [Index]es
+/// carry their element type, they can't be used to look up an entry of another type.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, any_arena::AnyArena};
+/// let (mut token, _) = first().unwrap().token();
+/// let mut arena = AnyArena::new();
+///
+/// let name = arena.insert(String::from("x"));
+/// let value = arena.insert(42u64);
+///
+/// arena.get_mut(&name, &mut token).push('y');
+/// assert_eq!(arena.get(&name, &token), "xy");
+/// assert_eq!(arena.get(&value, &token), &42);
+/// assert_eq!(arena.remove(value), 42);
+/// ```
+#[derive(Default)]
+pub struct AnyArena<const ID: usize> {
+    arenas: HashMap<TypeId, Box<dyn Any>>,
+    len: usize,
+}
+
+impl<const ID: usize> AnyArena<ID> {
+    pub fn new() -> Self {
+        Self {
+            arenas: HashMap::new(),
+            len: 0,
+        }
+    }
+
+    /// The arena holding every entry of type `T`, if any have been inserted.
+    pub fn arena<T: 'static>(&self) -> Option<&Arena<T, ID>> {
+        self.arenas.get(&TypeId::of::<T>()).map(|a| a.downcast_ref().unwrap())
+    }
+
+    fn arena_mut<T: 'static>(&mut self) -> &mut Arena<T, ID> {
+        self.arenas.entry(TypeId::of::<T>())
+            .or_insert_with(|| Box::new(Arena::<T, ID>::new()))
+            .downcast_mut()
+            .unwrap()
+    }
+
+    /// Total number of live entries, of every type.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    pub fn insert<T: 'static>(&mut self, value: T) -> Index<T, ID> {
+        self.len += 1;
+        self.arena_mut().insert(value)
+    }
+
+    /// # Panics
+    /// Panics if `index` belongs to a different arena.
+    pub fn remove<T: 'static>(&mut self, index: Index<T, ID>) -> T {
+        let value = self.arena_mut().remove(index);
+        self.len -= 1;
+        value
+    }
+
+    /// # Panics
+    /// Panics if `index` belongs to a different arena.
+    pub fn get<'a, T: 'static, U>(&'a self, index: &Index<T, ID>, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.arena().expect("Index used with the wrong AnyArena").get(index, token)
+    }
+
+    /// # Panics
+    /// Panics if `index` belongs to a different arena.
+    #[allow(clippy::mut_from_ref)]
+    pub fn get_mut<'a, T: 'static, U>(&'a self, index: &Index<T, ID>, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        self.arena().expect("Index used with the wrong AnyArena").get_mut(index, token)
+    }
+}
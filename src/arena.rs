@@ -0,0 +1,1203 @@
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ops::Deref;
+
+use crate::alloc::{Allocator, Global};
+use crate::cells::Cell;
+use crate::stats::ArenaCounts;
+#[cfg(feature = "arena-brand")]
+use crate::sync::{statics::AtomicU32, Ordering};
+use crate::tokens::TokenWith;
+
+/// An owning handle to an entry in an [Arena]. Entries can only be removed one by one by giving
+/// up their `Index`, so as long as an `Index` exists, its entry does too, unless it is removed in
+/// bulk with [Arena::clear], [Arena::retain] or [Arena::drain].
+///
+/// Indices are deliberately not `Clone`; use [Index::downgrade] to get a copyable, non-owning
+/// [WeakIndex] instead.
+///
+/// Indices also carry the arena's element type, so several arenas in the same family can't mix up
+/// their indices:
+/// ```compile_fail
+/// # use frankencell::{first, arena::Arena};
+/// struct Expr;
+/// struct Stmt;
+///
+/// let (token, _) = first().unwrap().token();
+/// let mut exprs = Arena::new();
+/// let mut stmts = Arena::new();
+///
+/// let e = exprs.insert(Expr);
+/// stmts.insert(Stmt);
+/// stmts.get(&e, &token);
+/// ```
+pub struct Index<T, const ID: usize> {
+    slot: usize,
+    generation: u32,
+    brand: Brand,
+    _type: PhantomData<fn() -> T>,
+}
+
+/// A non-owning handle to an entry in an [Arena]. Unlike an [Index], holding a `WeakIndex` doesn't
+/// keep the entry alive: it has to be checked with [Arena::upgrade] before it can be used.
+pub struct WeakIndex<T, const ID: usize> {
+    slot: usize,
+    generation: u32,
+    brand: Brand,
+    _type: PhantomData<fn() -> T>,
+}
+
+/// An [Index] that stores its slot number as a `u32`, making it 8 bytes instead of 16 on 64-bit
+/// targets (12 with the `arena-brand` feature). Useful for index-heavy structures like graphs, as
+/// long as the arena never holds more than `u32::MAX` slots. Created with [Index::compact], and
+/// usable anywhere an `Index` is.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, arena::{Arena, SmallIndex, SmallWeakIndex, WeakIndex}};
+/// let (token, _) = first().unwrap().token();
+/// let mut arena = Arena::new();
+///
+/// let a: SmallIndex<&str, 0> = arena.insert("a").compact();
+/// let weak: SmallWeakIndex<&str, 0> = a.downgrade();
+/// assert!(std::mem::size_of_val(&weak) < std::mem::size_of::<WeakIndex<&str, 0>>());
+///
+/// assert_eq!(arena.get(&a, &token), &"a");
+/// assert!(arena.contains(weak));
+/// arena.remove(a);
+/// assert!(!arena.contains(weak));
+/// ```
+pub struct SmallIndex<T, const ID: usize> {
+    slot: u32,
+    generation: u32,
+    brand: Brand,
+    _type: PhantomData<fn() -> T>,
+}
+
+/// A [WeakIndex] that stores its slot number as a `u32`. See [SmallIndex].
+pub struct SmallWeakIndex<T, const ID: usize> {
+    slot: u32,
+    generation: u32,
+    brand: Brand,
+    _type: PhantomData<fn() -> T>,
+}
+
+// Implemented by hand since deriving would require `T` to implement each trait as well
+macro_rules! impl_handle {
+    ($($name:ident),*) => {$(
+        impl<T, const ID: usize> PartialEq for $name<T, ID> {
+            fn eq(&self, other: &Self) -> bool {
+                self.slot == other.slot && self.generation == other.generation && self.brand == other.brand
+            }
+        }
+
+        impl<T, const ID: usize> Eq for $name<T, ID> {}
+
+        impl<T, const ID: usize> PartialOrd for $name<T, ID> {
+            fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+                Some(self.cmp(other))
+            }
+        }
+
+        impl<T, const ID: usize> Ord for $name<T, ID> {
+            fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+                (self.slot, self.generation, self.brand.get()).cmp(&(other.slot, other.generation, other.brand.get()))
+            }
+        }
+
+        impl<T, const ID: usize> Hash for $name<T, ID> {
+            fn hash<H: Hasher>(&self, state: &mut H) {
+                self.slot.hash(state);
+                self.generation.hash(state);
+                self.brand.hash(state);
+            }
+        }
+
+        impl<T, const ID: usize> Debug for $name<T, ID> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_struct(stringify!($name))
+                    .field("slot", &self.slot)
+                    .field("generation", &self.generation)
+                    .finish()
+            }
+        }
+
+        #[cfg(feature = "defmt")]
+        impl<T, const ID: usize> defmt::Format for $name<T, ID> {
+            fn format(&self, f: defmt::Formatter) {
+                defmt::write!(f, "{}<{}> {{ slot: {}, generation: {} }}", stringify!($name), ID, self.slot, self.generation)
+            }
+        }
+    )*}
+}
+
+impl_handle!(Index, WeakIndex, SmallIndex, SmallWeakIndex);
+
+impl<T, const ID: usize> Clone for WeakIndex<T, ID> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const ID: usize> Copy for WeakIndex<T, ID> {}
+
+impl<T, const ID: usize> Clone for SmallWeakIndex<T, ID> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const ID: usize> Copy for SmallWeakIndex<T, ID> {}
+
+/// Converts a [SmallWeakIndex] to slotmap's `KeyData`, so side tables can be attached to an
+/// arena's entries with a `slotmap::SecondaryMap` or `SparseSecondaryMap` the same way they would
+/// be to a `SlotMap`'s.
+///
+/// The conversion is lossy, which is why indices aren't slotmap keys themselves: slotmap versions
+/// are always odd, so only the low 31 bits of the generation survive, and with the `arena-brand`
+/// feature the arena the index belongs to is dropped. Indices rebuilt from `KeyData` (like the
+/// ones a secondary map's iterators yield) don't know which arena they came from, and are rejected
+/// by every arena when branding is on. A slot would need to be reused 2³¹ times for the lost
+/// generation bit to matter.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, arena::Arena};
+/// use slotmap::{DefaultKey, KeyData, SecondaryMap};
+///
+/// let (token, _) = first().unwrap().token();
+/// let mut arena = Arena::new();
+/// let a = arena.insert("a").compact();
+/// let b = arena.insert("b").compact();
+///
+/// let key = |index| DefaultKey::from(KeyData::from(index));
+/// let mut lengths = SecondaryMap::new();
+/// lengths.insert(key(a.downgrade()), arena.get(&a, &token).len());
+///
+/// assert_eq!(lengths.get(key(a.downgrade())), Some(&1));
+/// assert_eq!(lengths.get(key(b.downgrade())), None);
+/// ```
+#[cfg(feature = "slotmap")]
+impl<T, const ID: usize> From<SmallWeakIndex<T, ID>> for slotmap::KeyData {
+    fn from(index: SmallWeakIndex<T, ID>) -> Self {
+        let version = (index.generation as u64) << 1 | 1;
+        slotmap::KeyData::from_ffi(version << 32 | index.slot as u64)
+    }
+}
+
+#[cfg(feature = "slotmap")]
+impl<T, const ID: usize> From<slotmap::KeyData> for SmallWeakIndex<T, ID> {
+    fn from(data: slotmap::KeyData) -> Self {
+        let data = data.as_ffi();
+        Self {
+            slot: data as u32,
+            generation: (data >> 33) as u32,
+            brand: Brand::UNASSIGNED,
+            _type: PhantomData,
+        }
+    }
+}
+
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+#[serde(rename = "WeakIndex")]
+struct RawIndex<S> {
+    slot: S,
+    generation: u32,
+}
+
+/// Saves the slot and generation. Loaded indices only resolve in arenas loaded with
+/// [persist](crate::persist), where the entries keep their slots and generations.
+#[cfg(feature = "serde")]
+impl<T, const ID: usize> serde::Serialize for WeakIndex<T, ID> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawIndex { slot: self.slot, generation: self.generation }.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const ID: usize> serde::Deserialize<'de> for WeakIndex<T, ID> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RawIndex { slot, generation } = RawIndex::deserialize(deserializer)?;
+        Ok(Self::new(slot, generation, Brand::PERSISTED))
+    }
+}
+
+/// Saves the slot and generation, like [WeakIndex]'s implementation.
+#[cfg(feature = "serde")]
+impl<T, const ID: usize> serde::Serialize for SmallWeakIndex<T, ID> {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        RawIndex { slot: self.slot, generation: self.generation }.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de, T, const ID: usize> serde::Deserialize<'de> for SmallWeakIndex<T, ID> {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let RawIndex { slot, generation } = RawIndex::deserialize(deserializer)?;
+        Ok(Self { slot, generation, brand: Brand::PERSISTED, _type: PhantomData })
+    }
+}
+
+impl<T, const ID: usize> Index<T, ID> {
+    fn new(slot: usize, generation: u32, brand: Brand) -> Self {
+        Self { slot, generation, brand, _type: PhantomData }
+    }
+
+    pub fn downgrade(&self) -> WeakIndex<T, ID> {
+        WeakIndex::new(self.slot, self.generation, self.brand)
+    }
+
+    /// Converts this into a [SmallIndex], which is half the size on 64-bit targets.
+    ///
+    /// # Panics
+    /// Panics if the entry's slot number doesn't fit in a `u32`.
+    pub fn compact(self) -> SmallIndex<T, ID> {
+        SmallIndex {
+            slot: self.slot.try_into().expect("Arena slot doesn't fit in a SmallIndex"),
+            generation: self.generation,
+            brand: self.brand,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T, const ID: usize> WeakIndex<T, ID> {
+    fn new(slot: usize, generation: u32, brand: Brand) -> Self {
+        Self { slot, generation, brand, _type: PhantomData }
+    }
+
+    /// Converts this into a [SmallWeakIndex], which is half the size on 64-bit targets.
+    ///
+    /// # Panics
+    /// Panics if the entry's slot number doesn't fit in a `u32`.
+    pub fn compact(self) -> SmallWeakIndex<T, ID> {
+        SmallWeakIndex {
+            slot: self.slot.try_into().expect("Arena slot doesn't fit in a SmallWeakIndex"),
+            generation: self.generation,
+            brand: self.brand,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T, const ID: usize> SmallIndex<T, ID> {
+    pub fn downgrade(&self) -> SmallWeakIndex<T, ID> {
+        SmallWeakIndex {
+            slot: self.slot,
+            generation: self.generation,
+            brand: self.brand,
+            _type: PhantomData,
+        }
+    }
+}
+
+impl<T, const ID: usize> From<SmallIndex<T, ID>> for Index<T, ID> {
+    fn from(index: SmallIndex<T, ID>) -> Self {
+        Self::new(index.slot as usize, index.generation, index.brand)
+    }
+}
+
+impl<T, const ID: usize> From<SmallWeakIndex<T, ID>> for WeakIndex<T, ID> {
+    fn from(weak: SmallWeakIndex<T, ID>) -> Self {
+        Self::new(weak.slot as usize, weak.generation, weak.brand)
+    }
+}
+
+mod sealed {
+    pub trait Sealed {}
+}
+
+/// Handles that can be used to access an entry with [Arena::get] and [Arena::get_mut]: [Index],
+/// [SmallIndex] and [Upgraded].
+pub trait AsIndex<T, const ID: usize>: sealed::Sealed {
+    #[doc(hidden)]
+    fn slot(&self) -> usize;
+    #[doc(hidden)]
+    fn generation(&self) -> u32;
+    #[doc(hidden)]
+    fn brand(&self) -> u32;
+}
+
+impl<T, const ID: usize> sealed::Sealed for Index<T, ID> {}
+impl<T, const ID: usize> AsIndex<T, ID> for Index<T, ID> {
+    fn slot(&self) -> usize {
+        self.slot
+    }
+
+    fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    fn brand(&self) -> u32 {
+        self.brand.get()
+    }
+}
+
+impl<T, const ID: usize> sealed::Sealed for SmallIndex<T, ID> {}
+impl<T, const ID: usize> AsIndex<T, ID> for SmallIndex<T, ID> {
+    fn slot(&self) -> usize {
+        self.slot as usize
+    }
+
+    fn generation(&self) -> u32 {
+        self.generation
+    }
+
+    fn brand(&self) -> u32 {
+        self.brand.get()
+    }
+}
+
+impl<T, const ID: usize> sealed::Sealed for Upgraded<'_, T, ID> {}
+impl<T, const ID: usize> AsIndex<T, ID> for Upgraded<'_, T, ID> {
+    fn slot(&self) -> usize {
+        self.index.slot
+    }
+
+    fn generation(&self) -> u32 {
+        self.index.generation
+    }
+
+    fn brand(&self) -> u32 {
+        self.index.brand.get()
+    }
+}
+
+/// A [WeakIndex] that was checked to still be live, returned by [Arena::upgrade]. It borrows the
+/// arena so the entry can't be removed while it exists, and derefs to an [Index] usable with
+/// [Arena::get] and [Arena::get_mut].
+pub struct Upgraded<'a, T, const ID: usize> {
+    index: Index<T, ID>,
+    _arena: PhantomData<&'a ()>,
+}
+
+impl<T, const ID: usize> Deref for Upgraded<'_, T, ID> {
+    type Target = Index<T, ID>;
+
+    fn deref(&self) -> &Index<T, ID> {
+        &self.index
+    }
+}
+
+/// A snapshot of an [Arena]'s footprint, returned by [Arena::memory_usage].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MemoryUsage {
+    /// Number of live entries.
+    pub len: usize,
+    /// Number of slots whose entries were removed and which are waiting to be reused.
+    pub vacant: usize,
+    /// Number of entries that fit without reallocating.
+    pub capacity: usize,
+    /// Heap memory allocated by the arena, in bytes. Doesn't include memory owned by the entries
+    /// themselves.
+    pub bytes: usize,
+}
+
+/// Identifies an arena instance when the `arena-brand` feature is enabled, and is zero-sized
+/// otherwise.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+struct Brand(#[cfg(feature = "arena-brand")] u32);
+
+#[cfg(feature = "arena-brand")]
+impl Brand {
+    // Arenas get their brand on their first insertion, so that `Arena::new` can stay `const`
+    const UNASSIGNED: Self = Self(0);
+    // Shared by every arena loaded with `persist`, since indices saved inside entries can't know
+    // which arena they'll be loaded into
+    const PERSISTED: Self = Self(u32::MAX);
+
+    // Brands are never reused, so that two live arenas can't share one
+    fn assign(&mut self) {
+        static NEXT: AtomicU32 = AtomicU32::new(1);
+
+        if *self == Self::UNASSIGNED {
+            let brand = NEXT.fetch_update(Ordering::Relaxed, Ordering::Relaxed, |next| {
+                (next != Self::PERSISTED.0).then_some(next + 1)
+            });
+            *self = Self(brand.expect("Ran out of arena brands"));
+        }
+    }
+
+    fn get(self) -> u32 {
+        self.0
+    }
+}
+
+#[cfg(not(feature = "arena-brand"))]
+impl Brand {
+    const UNASSIGNED: Self = Self();
+    #[cfg(feature = "serde")]
+    const PERSISTED: Self = Self();
+
+    fn assign(&mut self) {}
+
+    fn get(self) -> u32 {
+        0
+    }
+}
+
+struct Slot<T, const ID: usize> {
+    generation: u32,
+    value: Option<Cell<T, ID>>,
+}
+
+/// A generational arena whose entries are accessed with an [Index] plus the family's token.
+/// Removing an entry bumps its slot's generation, so [WeakIndex]es to it can tell that it is gone
+/// even after the slot has been reused.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, arena::Arena};
+/// let (mut token, _) = first().unwrap().token();
+/// let mut arena = Arena::new();
+///
+/// let a = arena.insert('a');
+/// let b = arena.insert('b');
+/// let weak_b = b.downgrade();
+///
+/// *arena.get_mut(&a, &mut token) = 'ä';
+/// assert_eq!(arena.get(&a, &token), &'ä');
+///
+/// assert_eq!(arena.upgrade(weak_b).map(|b| *arena.get(&b, &token)), Some('b'));
+/// assert_eq!(arena.remove(b), 'b');
+/// assert!(arena.upgrade(weak_b).is_none());
+/// ```
+pub struct Arena<T, const ID: usize, A: Allocator = Global> {
+    #[cfg(feature = "allocator-api")]
+    slots: Vec<Slot<T, ID>, A>,
+    #[cfg(feature = "allocator-api")]
+    free: Vec<usize, A>,
+    #[cfg(not(feature = "allocator-api"))]
+    slots: Vec<Slot<T, ID>>,
+    #[cfg(not(feature = "allocator-api"))]
+    free: Vec<usize>,
+    len: usize,
+    brand: Brand,
+    counts: ArenaCounts<ID>,
+    #[cfg(not(feature = "allocator-api"))]
+    _alloc: PhantomData<A>,
+}
+
+impl<T, const ID: usize> Default for Arena<T, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const ID: usize, A: Allocator> Debug for Arena<T, ID, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "Arena<{}, {}> {{ len: {} }}", std::any::type_name::<T>(), ID, self.len)
+    }
+}
+
+impl<T, const ID: usize> Arena<T, ID> {
+    pub const fn new() -> Self {
+        Self {
+            slots: Vec::new(),
+            free: Vec::new(),
+            len: 0,
+            brand: Brand::UNASSIGNED,
+            counts: ArenaCounts::new(),
+            #[cfg(not(feature = "allocator-api"))]
+            _alloc: PhantomData,
+        }
+    }
+
+    /// Creates an empty arena with room for at least `capacity` entries.
+    pub fn with_capacity(capacity: usize) -> Self {
+        let mut arena = Self::new();
+        arena.reserve(capacity);
+        arena
+    }
+}
+
+#[cfg(feature = "allocator-api")]
+impl<T, const ID: usize, A: Allocator> Arena<T, ID, A> {
+    /// Like [Arena::new], but allocates in `alloc` instead of the global allocator.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// # use frankencell::{first, arena::Arena};
+    /// use std::alloc::System;
+    ///
+    /// let (token, _) = first().unwrap().token();
+    /// let mut arena = Arena::new_in(System);
+    ///
+    /// let a = arena.insert('a');
+    /// assert_eq!(arena.get(&a, &token), &'a');
+    /// ```
+    pub fn new_in(alloc: A) -> Self where A: Clone {
+        Self {
+            slots: Vec::new_in(alloc.clone()),
+            free: Vec::new_in(alloc),
+            len: 0,
+            brand: Brand::UNASSIGNED,
+            counts: ArenaCounts::new(),
+        }
+    }
+}
+
+impl<T, const ID: usize, A: Allocator> Arena<T, ID, A> {
+    /// Number of live entries.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Number of entries the arena can hold without reallocating.
+    pub fn capacity(&self) -> usize {
+        self.slots.capacity()
+    }
+
+    /// Makes room for at least `additional` more entries, counting slots waiting to be reused.
+    pub fn reserve(&mut self, additional: usize) {
+        self.slots.reserve(additional.saturating_sub(self.free.len()));
+        self.free.reserve(self.slots.capacity() - self.free.len());
+    }
+
+    /// Releases unused capacity. Vacant slots are kept, since forgetting their generations would
+    /// let stale [WeakIndex]es match new entries.
+    pub fn shrink_to_fit(&mut self) {
+        self.slots.shrink_to_fit();
+        self.free.shrink_to_fit();
+    }
+
+    /// # Example
+    /// ```rust
+    /// # use frankencell::arena::Arena;
+    /// let mut arena = Arena::<u64, 0>::with_capacity(8);
+    /// let a = arena.insert(1);
+    /// arena.insert(2);
+    /// arena.remove(a);
+    ///
+    /// let usage = arena.memory_usage();
+    /// assert_eq!((usage.len, usage.vacant), (1, 1));
+    /// assert!(usage.capacity >= 8);
+    /// ```
+    pub fn memory_usage(&self) -> MemoryUsage {
+        MemoryUsage {
+            len: self.len,
+            vacant: self.free.len(),
+            capacity: self.slots.capacity(),
+            bytes: self.slots.capacity() * std::mem::size_of::<Slot<T, ID>>()
+                + self.free.capacity() * std::mem::size_of::<usize>(),
+        }
+    }
+
+    pub fn insert(&mut self, value: T) -> Index<T, ID> {
+        self.brand.assign();
+        self.len += 1;
+        self.counts.record(0, 1);
+
+        match self.free.pop() {
+            Some(slot) => {
+                let entry = &mut self.slots[slot];
+                entry.value = Some(Cell::new(value));
+                Index::new(slot, entry.generation, self.brand)
+            }
+            None => {
+                #[cfg(feature = "tracing")]
+                if self.slots.len() == self.slots.capacity() {
+                    tracing::debug!(family = ID, len = self.slots.len(), "Arena growing");
+                }
+
+                self.counts.record(1, 0);
+                self.slots.push(Slot {
+                    generation: 0,
+                    value: Some(Cell::new(value)),
+                });
+                Index::new(self.slots.len() - 1, 0, self.brand)
+            }
+        }
+    }
+
+    /// Inserts the value returned by `f`, which is given a [WeakIndex] to the entry being created.
+    /// This allows self-referential and cyclic structures to be built without a second pass to
+    /// patch up back-pointers.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, arena::{Arena, WeakIndex}};
+    /// struct Node<const ID: usize> {
+    ///     this: WeakIndex<Node<ID>, ID>,
+    ///     next: Option<WeakIndex<Node<ID>, ID>>,
+    /// }
+    ///
+    /// let (token, _) = first().unwrap().token();
+    /// let mut arena = Arena::new();
+    ///
+    /// // A single-node cycle
+    /// let node = arena.insert_cyclic(|this| Node { this, next: Some(this) });
+    ///
+    /// let n = arena.get(&node, &token);
+    /// assert_eq!(n.this, node.downgrade());
+    /// assert_eq!(n.next, Some(node.downgrade()));
+    /// ```
+    pub fn insert_cyclic(&mut self, f: impl FnOnce(WeakIndex<T, ID>) -> T) -> Index<T, ID> {
+        self.brand.assign();
+        let weak = match self.free.last() {
+            Some(&slot) => WeakIndex::new(slot, self.slots[slot].generation, self.brand),
+            None => WeakIndex::new(self.slots.len(), 0, self.brand),
+        };
+
+        let index = self.insert(f(weak));
+        debug_assert_eq!(index.downgrade(), weak);
+        index
+    }
+
+    /// Removes an entry, invalidating every [WeakIndex] to it.
+    ///
+    /// # Panics
+    /// Panics if `index` belongs to a different arena.
+    pub fn remove(&mut self, index: impl Into<Index<T, ID>>) -> T {
+        let index = index.into();
+        assert!(self.cell(index.slot, index.generation, index.brand.get()).is_some(), "Index used with the wrong Arena, or after a bulk removal");
+        self.vacate(index.slot).unwrap()
+    }
+
+    // Empties a slot, if it is occupied, and bumps its generation
+    fn vacate(&mut self, slot: usize) -> Option<T> {
+        let entry = &mut self.slots[slot];
+        let value = entry.value.take()?.into_inner();
+
+        entry.generation = entry.generation.wrapping_add(1);
+        self.free.push(slot);
+        self.len -= 1;
+        self.counts.record(0, -1);
+
+        Some(value)
+    }
+
+    /// Removes every entry, invalidating every [Index] and [WeakIndex] to them.
+    pub fn clear(&mut self) {
+        self.drain();
+    }
+
+    /// Removes every entry for which `f` returns false. Removed entries' [Index]es and
+    /// [WeakIndex]es are invalidated.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::arena::Arena;
+    /// let mut arena = Arena::<_, 0>::new();
+    /// let weak: Vec<_> = (0..6).map(|i| arena.insert(i).downgrade()).collect();
+    ///
+    /// arena.retain(|_, i| *i % 2 == 0);
+    /// assert!(arena.contains(weak[2]));
+    /// assert!(!arena.contains(weak[3]));
+    ///
+    /// let drained: Vec<_> = arena.drain().map(|(_, i)| i).collect();
+    /// assert_eq!(drained, [0, 2, 4]);
+    /// assert!(arena.is_empty());
+    /// ```
+    pub fn retain(&mut self, mut f: impl FnMut(WeakIndex<T, ID>, &mut T) -> bool) {
+        for slot in 0..self.slots.len() {
+            let entry = &mut self.slots[slot];
+            let generation = entry.generation;
+
+            if let Some(value) = &mut entry.value {
+                if !f(WeakIndex::new(slot, generation, self.brand), value.get_mut()) {
+                    self.vacate(slot);
+                }
+            }
+        }
+    }
+
+    /// Removes every entry, yielding each one along with the [WeakIndex] it used to have. Every
+    /// entry is removed even if the iterator is dropped early.
+    pub fn drain(&mut self) -> Drain<'_, T, ID, A> {
+        Drain { arena: self, next: 0 }
+    }
+
+    fn cell(&self, slot: usize, generation: u32, brand: u32) -> Option<&Cell<T, ID>> {
+        if brand != self.brand.get() {
+            return None;
+        }
+
+        self.slots.get(slot)
+            .filter(|s| s.generation == generation)
+            .and_then(|s| s.value.as_ref())
+    }
+
+    /// # Panics
+    /// Panics if `index` belongs to a different arena.
+    pub fn get<'a, U>(&'a self, index: &impl AsIndex<T, ID>, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.cell(index.slot(), index.generation(), index.brand())
+            .expect("Index used with the wrong Arena, or after a bulk removal")
+            .borrow(token)
+    }
+
+    /// # Panics
+    /// Panics if `index` belongs to a different arena.
+    #[allow(clippy::mut_from_ref)]
+    pub fn get_mut<'a, U>(&'a self, index: &impl AsIndex<T, ID>, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        self.cell(index.slot(), index.generation(), index.brand())
+            .expect("Index used with the wrong Arena, or after a bulk removal")
+            .borrow_mut(token)
+    }
+
+    /// Whether the entry `weak` points to is still live.
+    pub fn contains(&self, weak: impl Into<WeakIndex<T, ID>>) -> bool {
+        let weak = weak.into();
+        self.cell(weak.slot, weak.generation, weak.brand.get()).is_some()
+    }
+
+    /// Checks that the entry `weak` points to is still live, and if so returns an [Index] to it
+    /// that is valid for as long as the arena is borrowed.
+    pub fn upgrade(&self, weak: impl Into<WeakIndex<T, ID>>) -> Option<Upgraded<'_, T, ID>> {
+        let weak = weak.into();
+        self.contains(weak).then_some(Upgraded {
+            index: Index::new(weak.slot, weak.generation, weak.brand),
+            _arena: PhantomData,
+        })
+    }
+
+    /// Iterates over every live entry, in slot order.
+    pub fn iter<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> impl Iterator<Item = (WeakIndex<T, ID>, &'a T)> + 'a {
+        self.slots.iter().enumerate().filter_map(move |(slot, s)| {
+            s.value.as_ref().map(|value| {
+                (WeakIndex::new(slot, s.generation, self.brand), value.borrow(token))
+            })
+        })
+    }
+
+    /// Pairs the arena with a token so entries can be read with indexing syntax. The token stays
+    /// borrowed for as long as the view exists.
+    ///
+    /// Indexing the arena itself with an `(index, token)` pair isn't possible, since
+    /// [std::ops::Index] can't tie the returned reference to the token's borrow.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, arena::Arena};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let mut arena = Arena::new();
+    /// let a = arena.insert(1);
+    /// let b = arena.insert(2);
+    ///
+    /// let mut view = arena.view_mut(&mut token);
+    /// view[&a] += view[&b];
+    ///
+    /// assert_eq!(arena.view(&token)[&a], 3);
+    /// ```
+    pub fn view<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> ArenaView<'a, T, U, ID, A> {
+        ArenaView { arena: self, token }
+    }
+
+    /// Like [Self::view], but also allows entries to be mutated through `IndexMut`.
+    pub fn view_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> ArenaViewMut<'a, T, U, ID, A> {
+        ArenaViewMut { arena: self, token }
+    }
+}
+
+/// An [Arena] paired with a `&Token`, returned by [Arena::view].
+pub struct ArenaView<'a, T, U, const ID: usize, A: Allocator = Global> {
+    arena: &'a Arena<T, ID, A>,
+    token: &'a TokenWith<U, ID>,
+}
+
+/// An [Arena] paired with a `&mut Token`, returned by [Arena::view_mut].
+pub struct ArenaViewMut<'a, T, U, const ID: usize, A: Allocator = Global> {
+    arena: &'a Arena<T, ID, A>,
+    token: &'a mut TokenWith<U, ID>,
+}
+
+impl<T, U, K: AsIndex<T, ID>, const ID: usize, A: Allocator> std::ops::Index<&K> for ArenaView<'_, T, U, ID, A> {
+    type Output = T;
+
+    fn index(&self, index: &K) -> &T {
+        self.arena.get(index, self.token)
+    }
+}
+
+impl<T, U, K: AsIndex<T, ID>, const ID: usize, A: Allocator> std::ops::Index<&K> for ArenaViewMut<'_, T, U, ID, A> {
+    type Output = T;
+
+    fn index(&self, index: &K) -> &T {
+        self.arena.get(index, self.token)
+    }
+}
+
+impl<T, U, K: AsIndex<T, ID>, const ID: usize, A: Allocator> std::ops::IndexMut<&K> for ArenaViewMut<'_, T, U, ID, A> {
+    fn index_mut(&mut self, index: &K) -> &mut T {
+        self.arena.get_mut(index, self.token)
+    }
+}
+
+/// Iterator returned by [Arena::drain].
+pub struct Drain<'a, T, const ID: usize, A: Allocator = Global> {
+    arena: &'a mut Arena<T, ID, A>,
+    next: usize,
+}
+
+impl<T, const ID: usize, A: Allocator> Iterator for Drain<'_, T, ID, A> {
+    type Item = (WeakIndex<T, ID>, T);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.next < self.arena.slots.len() {
+            let slot = self.next;
+            let generation = self.arena.slots[slot].generation;
+            self.next += 1;
+
+            if let Some(value) = self.arena.vacate(slot) {
+                return Some((WeakIndex::new(slot, generation, self.arena.brand), value));
+            }
+        }
+
+        None
+    }
+}
+
+impl<T, const ID: usize, A: Allocator> Drop for Drain<'_, T, ID, A> {
+    fn drop(&mut self) {
+        self.for_each(drop);
+    }
+}
+
+/// Extra data attached to the entries of an [Arena], keyed by their [WeakIndex]es, like
+/// slotmap's `SecondaryMap`. Values live in cells of the same family, so one token governs both,
+/// and optional per-entry data can be layered on without widening the arena's element type.
+///
+/// The secondary arena doesn't hear about removals from the primary one, so a value attached to a
+/// removed entry stays reachable through the entry's old key until a newer entry in the same slot
+/// is given a value, or it is cleaned up with [Self::retain_live]. Keys to entries that are known
+/// to be live never see stale values.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, arena::{Arena, SecondaryArena}};
+/// let (mut token, _) = first().unwrap().token();
+/// let mut nodes = Arena::new();
+/// let mut labels = SecondaryArena::new();
+///
+/// let a = nodes.insert(1.5);
+/// let b = nodes.insert(2.5);
+/// labels.insert(a.downgrade(), String::from("start"));
+///
+/// labels.get_mut(a.downgrade(), &mut token).unwrap().push('!');
+/// assert_eq!(labels.get(a.downgrade(), &token).map(String::as_str), Some("start!"));
+/// assert_eq!(labels.get(b.downgrade(), &token), None);
+///
+/// nodes.remove(a);
+/// labels.retain_live(&nodes);
+/// assert!(labels.is_empty());
+/// ```
+pub struct SecondaryArena<K, V, const ID: usize> {
+    slots: Vec<Option<(WeakIndex<K, ID>, Cell<V, ID>)>>,
+    len: usize,
+}
+
+impl<K, V, const ID: usize> Default for SecondaryArena<K, V, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K, V, const ID: usize> SecondaryArena<K, V, ID> {
+    pub const fn new() -> Self {
+        Self { slots: Vec::new(), len: 0 }
+    }
+
+    /// The number of values, including any attached to entries that have since been removed.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn cell(&self, key: WeakIndex<K, ID>) -> Option<&Cell<V, ID>> {
+        match self.slots.get(key.slot) {
+            Some(Some((k, value))) if *k == key => Some(value),
+            _ => None,
+        }
+    }
+
+    /// Attaches `value` to `key`'s entry, returning the value it replaced, if any.
+    pub fn insert(&mut self, key: impl Into<WeakIndex<K, ID>>, value: V) -> Option<V> {
+        let key = key.into();
+        if key.slot >= self.slots.len() {
+            self.slots.resize_with(key.slot + 1, || None);
+        }
+
+        match self.slots[key.slot].replace((key, Cell::new(value))) {
+            Some((old, value)) if old == key => Some(value.into_inner()),
+            Some(_) => None,
+            None => {
+                self.len += 1;
+                None
+            }
+        }
+    }
+
+    pub fn remove(&mut self, key: impl Into<WeakIndex<K, ID>>) -> Option<V> {
+        let key = key.into();
+        self.cell(key)?;
+        self.len -= 1;
+        self.slots[key.slot].take().map(|(_, value)| value.into_inner())
+    }
+
+    pub fn contains(&self, key: impl Into<WeakIndex<K, ID>>) -> bool {
+        self.cell(key.into()).is_some()
+    }
+
+    pub fn get<'a, U>(&'a self, key: impl Into<WeakIndex<K, ID>>, token: &'a TokenWith<U, ID>) -> Option<&'a V> {
+        self.cell(key.into()).map(|value| value.borrow(token))
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn get_mut<'a, U>(&'a self, key: impl Into<WeakIndex<K, ID>>, token: &'a mut TokenWith<U, ID>) -> Option<&'a mut V> {
+        self.cell(key.into()).map(|value| value.borrow_mut(token))
+    }
+
+    /// Iterates over every value along with its key, in slot order.
+    pub fn iter<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> impl Iterator<Item = (WeakIndex<K, ID>, &'a V)> + 'a {
+        self.slots.iter().flatten().map(move |(key, value)| (*key, value.borrow(token)))
+    }
+
+    /// Drops every value whose entry is no longer live in `arena`.
+    pub fn retain_live<A: Allocator>(&mut self, arena: &Arena<K, ID, A>) {
+        for slot in &mut self.slots {
+            if slot.as_ref().is_some_and(|(key, _)| !arena.contains(*key)) {
+                *slot = None;
+                self.len -= 1;
+            }
+        }
+    }
+}
+
+impl<T, const ID: usize> Arena<T, ID> {
+    // Inserts every entry, then removes the flagged ones so generated arenas also have free and
+    // reused slots
+    #[cfg(any(feature = "arbitrary", feature = "proptest"))]
+    fn from_entries(entries: Vec<(T, bool)>) -> Self {
+        let mut arena = Self::new();
+        let mut removed = Vec::new();
+
+        for (value, remove) in entries {
+            let index = arena.insert(value);
+            if remove {
+                removed.push(index);
+            }
+        }
+        for index in removed {
+            arena.remove(index);
+        }
+
+        arena
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<T, const ID: usize> Arena<T, ID> {
+    // Rebuilds an arena from each slot's generation and value, keeping every entry in its slot so
+    // that saved indices still point to it
+    pub(crate) fn from_slots(slots: impl IntoIterator<Item = (u32, Option<T>)>) -> Self {
+        let mut arena = Self::new();
+        arena.brand = Brand::PERSISTED;
+
+        for (slot, (generation, value)) in slots.into_iter().enumerate() {
+            match value {
+                Some(_) => arena.len += 1,
+                None => arena.free.push(slot),
+            }
+            arena.slots.push(Slot { generation, value: value.map(Cell::new) });
+        }
+
+        arena.counts.record(arena.slots.len(), arena.len as isize);
+        arena
+    }
+
+    // Every slot's generation and value, including vacant ones
+    pub(crate) fn slots<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> impl Iterator<Item = (u32, Option<&'a T>)> + 'a {
+        self.slots.iter().map(move |s| (s.generation, s.value.as_ref().map(|value| value.borrow(token))))
+    }
+}
+
+#[cfg(feature = "rayon")]
+impl<T: Send, const ID: usize, A: Allocator> Arena<T, ID, A> {
+    /// Inserts every value from a parallel iterator, returning their [Index]es in the iterator's
+    /// order. The entries are built in parallel and then appended to the end of the arena, so
+    /// vacant slots aren't reused.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, arena::Arena};
+    /// use rayon::prelude::*;
+    ///
+    /// let (token, _) = first().unwrap().token();
+    /// let mut arena = Arena::new();
+    ///
+    /// let indices = arena.par_extend((0..100_000u64).into_par_iter().map(|i| i * i));
+    /// assert_eq!(arena.get(&indices[3], &token), &9);
+    /// ```
+    pub fn par_extend(&mut self, values: impl rayon::iter::IntoParallelIterator<Item = T>) -> Vec<Index<T, ID>> {
+        use rayon::prelude::*;
+
+        self.brand.assign();
+        let brand = self.brand;
+
+        let slots: Vec<_> = values.into_par_iter()
+            .map(|value| Slot { generation: 0, value: Some(Cell::new(value)) })
+            .collect();
+
+        let start = self.slots.len();
+        self.counts.record(slots.len(), slots.len() as isize);
+        self.len += slots.len();
+        self.slots.extend(slots);
+
+        (start..self.slots.len()).into_par_iter()
+            .map(|slot| Index::new(slot, 0, brand))
+            .collect()
+    }
+}
+
+/// Builds an arena in parallel. Since no [Index]es are handed out, entries are found with
+/// [Arena::iter] and [Arena::upgrade]; use [Arena::par_extend] to keep the indices.
+#[cfg(feature = "rayon")]
+impl<T: Send, const ID: usize> rayon::iter::FromParallelIterator<T> for Arena<T, ID> {
+    fn from_par_iter<I: rayon::iter::IntoParallelIterator<Item = T>>(values: I) -> Self {
+        let mut arena = Self::new();
+        arena.par_extend(values);
+        arena
+    }
+}
+
+/// Generates an arena with arbitrary entries, some of which have already been removed. Since no
+/// [Index]es are handed out, entries are found with [Arena::iter] and [Arena::upgrade].
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>, const ID: usize> arbitrary::Arbitrary<'a> for Arena<T, ID> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        u.arbitrary_iter()?.collect::<arbitrary::Result<_>>().map(Self::from_entries)
+    }
+}
+
+/// Generates an arena with entries from `element`, some of which have already been removed. Since
+/// no [Index]es are handed out, entries are found with [Arena::iter] and [Arena::upgrade].
+///
+/// # Example
+/// ```rust
+/// # use frankencell::arena::{self, Arena};
+/// use proptest::prelude::*;
+///
+/// proptest!(|(arena in arena::strategy::<_, 0>(0..100u32, 0..10))| {
+///     prop_assert!(arena.len() < 10);
+/// });
+/// ```
+#[cfg(feature = "proptest")]
+pub fn strategy<S: proptest::strategy::Strategy, const ID: usize>(
+    element: S,
+    size: impl Into<proptest::collection::SizeRange>,
+) -> impl proptest::strategy::Strategy<Value = Arena<S::Value, ID>> {
+    use proptest::strategy::Strategy;
+    proptest::collection::vec((element, proptest::bool::ANY), size).prop_map(Arena::from_entries)
+}
+
+#[cfg(feature = "proptest")]
+impl<T: proptest::arbitrary::Arbitrary + 'static, const ID: usize> proptest::arbitrary::Arbitrary for Arena<T, ID> {
+    type Parameters = (proptest::collection::SizeRange, T::Parameters);
+    type Strategy = proptest::strategy::BoxedStrategy<Self>;
+
+    fn arbitrary_with((size, args): Self::Parameters) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+        strategy(T::arbitrary_with(args), size).boxed()
+    }
+}
+
+#[test]
+fn arena_reuse_test() {
+    use crate::TokenBuilder;
+
+    let (token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let mut arena = Arena::new();
+
+    let a = arena.insert(1);
+    let weak_a = a.downgrade();
+    arena.remove(a);
+
+    // `b` reuses `a`'s slot, but with a different generation
+    let b = arena.insert(2);
+    assert_eq!(b.slot, weak_a.slot);
+    assert!(!arena.contains(weak_a));
+    assert!(arena.contains(b.downgrade()));
+
+    assert_eq!(arena.len(), 1);
+    assert_eq!(arena.iter(&token).map(|(_, v)| *v).collect::<Vec<_>>(), [2]);
+}
+
+// Compiles only as long as dropping an arena can't touch its entries, with or without `stats`
+#[test]
+fn arena_of_references_test() {
+    let mut arena = Arena::<_, 1000>::new();
+    let value = String::from("a");
+    arena.insert(&value);
+    drop(value);
+}
+
+#[cfg(feature = "arbitrary")]
+#[test]
+fn arbitrary_arena_test() {
+    let data = [0xAB; 64];
+    let arena: Arena<u8, 1000> = arbitrary::Arbitrary::arbitrary(&mut arbitrary::Unstructured::new(&data)).unwrap();
+    assert!(arena.slots.len() >= arena.len());
+    assert_eq!(arena.slots.len() - arena.len(), arena.free.len());
+}
+
+#[cfg(feature = "slotmap")]
+#[test]
+fn slotmap_key_test() {
+    use slotmap::KeyData;
+
+    let mut arena = Arena::<_, 1000>::new();
+    let a = arena.insert(()).compact();
+    arena.remove(a);
+    let b = arena.insert(()).compact().downgrade();
+
+    let key = SmallWeakIndex::<(), 1000>::from(KeyData::from(b));
+    assert_eq!((key.slot, key.generation), (0, 1));
+    assert_ne!(KeyData::from(b), KeyData::default());
+}
+
+#[cfg(feature = "arena-brand")]
+#[test]
+fn arena_brand_test() {
+    let mut a = Arena::<_, 1000>::new();
+    let mut b = Arena::<_, 1000>::new();
+
+    // Both indices point at slot 0, generation 0
+    let in_a = a.insert('a');
+    let in_b = b.insert('b');
+
+    assert!(a.contains(in_a.downgrade()));
+    assert!(!a.contains(in_b.downgrade()));
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| a.remove(in_b))).is_err());
+}
+
+#[test]
+fn secondary_arena_stale_test() {
+    use crate::TokenBuilder;
+
+    let (token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let mut arena = Arena::new();
+    let mut secondary = SecondaryArena::new();
+
+    let a = arena.insert('a');
+    let weak_a = a.downgrade();
+    secondary.insert(weak_a, 1);
+    arena.remove(a);
+
+    // The new entry reuses the slot, and replaces the stale value without returning it
+    let b = arena.insert('b');
+    assert_eq!(secondary.insert(b.downgrade(), 2), None);
+    assert_eq!(secondary.len(), 1);
+    assert_eq!(secondary.get(weak_a, &token), None);
+    assert_eq!(secondary.remove(b.downgrade()), Some(2));
+    assert!(secondary.is_empty());
+}
@@ -0,0 +1,104 @@
+//! An [arrayvec](::arrayvec)-backed, fixed-capacity vector of token-gated elements.
+
+use ::arrayvec::{ArrayVec, CapacityError};
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// A vector that stores up to `N` elements inline and never allocates, and that can be read with
+/// a `&Token` and changed with a `&mut Token`, like [TokenString](crate::string::TokenString).
+/// Pushing onto a full vector fails instead of growing it.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, arrayvec::ArrayCellVec};
+/// let (mut token, _) = first().unwrap().token();
+/// let pending: ArrayCellVec<&str, 2, 0> = ArrayCellVec::new();
+///
+/// pending.push(&mut token, "a").unwrap();
+/// pending.push(&mut token, "b").unwrap();
+/// assert_eq!(pending.push(&mut token, "c").unwrap_err().element(), "c");
+///
+/// assert!(pending.is_full(&token));
+/// assert_eq!(pending.pop(&mut token), Some("b"));
+/// ```
+#[repr(transparent)]
+pub struct ArrayCellVec<T, const N: usize, const ID: usize> {
+    inner: Cell<ArrayVec<T, N>, ID>,
+}
+
+impl<T, const N: usize, const ID: usize> Default for ArrayCellVec<T, N, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize, const ID: usize> ArrayCellVec<T, N, ID> {
+    pub const fn new() -> Self {
+        Self {
+            inner: Cell::new(ArrayVec::new_const()),
+        }
+    }
+
+    pub fn into_inner(self) -> ArrayVec<T, N> {
+        self.inner.into_inner()
+    }
+
+    pub fn as_slice<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a [T] {
+        self.inner.borrow(token)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn as_mut_slice<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut [T] {
+        self.inner.borrow_mut(token)
+    }
+
+    /// Mutable access to the underlying `ArrayVec`, for anything not covered by the methods below.
+    #[allow(clippy::mut_from_ref)]
+    pub fn as_mut_arrayvec<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut ArrayVec<T, N> {
+        self.inner.borrow_mut(token)
+    }
+
+    pub fn len<U>(&self, token: &TokenWith<U, ID>) -> usize {
+        self.inner.borrow(token).len()
+    }
+
+    pub fn is_empty<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.inner.borrow(token).is_empty()
+    }
+
+    pub fn is_full<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.inner.borrow(token).is_full()
+    }
+
+    /// Appends `value`, or hands it back if the vector is full.
+    pub fn push<U>(&self, token: &mut TokenWith<U, ID>, value: T) -> Result<(), CapacityError<T>> {
+        self.as_mut_arrayvec(token).try_push(value)
+    }
+
+    pub fn pop<U>(&self, token: &mut TokenWith<U, ID>) -> Option<T> {
+        self.as_mut_arrayvec(token).pop()
+    }
+
+    /// Inserts `value` at `index`, or hands it back if the vector is full.
+    ///
+    /// # Panics
+    /// Panics if `index` is greater than the length.
+    pub fn insert<U>(&self, token: &mut TokenWith<U, ID>, index: usize, value: T) -> Result<(), CapacityError<T>> {
+        self.as_mut_arrayvec(token).try_insert(index, value)
+    }
+
+    /// See [ArrayVec::remove]
+    pub fn remove<U>(&self, token: &mut TokenWith<U, ID>, index: usize) -> T {
+        self.as_mut_arrayvec(token).remove(index)
+    }
+
+    /// See [ArrayVec::truncate]
+    pub fn truncate<U>(&self, token: &mut TokenWith<U, ID>, len: usize) {
+        self.as_mut_arrayvec(token).truncate(len)
+    }
+
+    pub fn clear<U>(&self, token: &mut TokenWith<U, ID>) {
+        self.as_mut_arrayvec(token).clear()
+    }
+}
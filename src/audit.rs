@@ -0,0 +1,159 @@
+use std::panic::Location;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// Whether an [AuditEntry] was recorded by [AuditedCell::borrow] or [AuditedCell::borrow_mut].
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AccessKind {
+    Borrow,
+    BorrowMut,
+}
+
+/// A single access to an [AuditedCell], as recorded in its family's log.
+#[derive(Clone, Copy, Debug)]
+pub struct AuditEntry {
+    pub kind: AccessKind,
+    pub location: &'static Location<'static>,
+    pub type_name: &'static str,
+}
+
+/// A [Cell] that records every `borrow`/`borrow_mut` along with its call site into a log shared
+/// by every `AuditedCell` in the same family. The log can be read back with
+/// [TokenWith::audit_log].
+///
+/// Recording only happens when the `audit` feature is enabled. Without it, an `AuditedCell` is
+/// exactly as cheap as a plain `Cell` and the log is always empty.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, audit::AuditedCell};
+/// let (mut token, _) = first().unwrap().token();
+/// let counter = AuditedCell::new(0);
+///
+/// *counter.borrow_mut(&mut token) += 1;
+///
+/// for entry in token.audit_log() {
+///     println!("{:?} at {}", entry.kind, entry.location);
+/// }
+/// ```
+#[derive(Default)]
+#[repr(transparent)]
+pub struct AuditedCell<T, const ID: usize> {
+    cell: Cell<T, ID>,
+}
+
+impl<T, const ID: usize> AuditedCell<T, ID> {
+    pub const fn new(t: T) -> Self {
+        Self {
+            cell: Cell::new(t),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.cell.into_inner()
+    }
+
+    /// Reinterpret a `&mut self` as a `&mut T`. Since this doesn't involve the token, it is not
+    /// recorded.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.cell.get_mut()
+    }
+
+    /// Same as [Cell::borrow], but records the caller in the family's log.
+    #[track_caller]
+    pub fn borrow<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T {
+        log::record::<T>(ID, AccessKind::Borrow, Location::caller());
+        self.cell.borrow(token)
+    }
+
+    /// Same as [Cell::borrow_mut], but records the caller in the family's log.
+    #[track_caller]
+    #[allow(clippy::mut_from_ref)]
+    pub fn borrow_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        log::record::<T>(ID, AccessKind::BorrowMut, Location::caller());
+        self.cell.borrow_mut(token)
+    }
+}
+
+impl<U, const ID: usize> TokenWith<U, ID> {
+    /// Every access made to an [AuditedCell] of this family so far, oldest first.
+    pub fn audit_log(&self) -> Vec<AuditEntry> {
+        log::entries(ID)
+    }
+
+    /// Empties this family's audit log.
+    pub fn clear_audit_log(&mut self) {
+        log::clear(ID)
+    }
+}
+
+#[cfg(feature = "audit")]
+mod log {
+    use std::collections::BTreeMap;
+    use std::panic::Location;
+    use std::sync::Mutex;
+
+    use super::{AccessKind, AuditEntry};
+
+    static LOGS: Mutex<BTreeMap<usize, Vec<AuditEntry>>> = Mutex::new(BTreeMap::new());
+
+    fn logs() -> std::sync::MutexGuard<'static, BTreeMap<usize, Vec<AuditEntry>>> {
+        // The log is only ever appended to, so a panic elsewhere can't leave it inconsistent.
+        LOGS.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(super) fn record<T>(id: usize, kind: AccessKind, location: &'static Location<'static>) {
+        logs().entry(id).or_default().push(AuditEntry {
+            kind,
+            location,
+            type_name: std::any::type_name::<T>(),
+        });
+    }
+
+    pub(super) fn entries(id: usize) -> Vec<AuditEntry> {
+        logs().get(&id).cloned().unwrap_or_default()
+    }
+
+    pub(super) fn clear(id: usize) {
+        logs().remove(&id);
+    }
+}
+
+#[cfg(not(feature = "audit"))]
+mod log {
+    use std::panic::Location;
+
+    use super::{AccessKind, AuditEntry};
+
+    #[inline(always)]
+    pub(super) fn record<T>(_: usize, _: AccessKind, _: &'static Location<'static>) {}
+
+    pub(super) fn entries(_: usize) -> Vec<AuditEntry> {
+        Vec::new()
+    }
+
+    pub(super) fn clear(_: usize) {}
+}
+
+#[cfg(feature = "audit")]
+#[test]
+fn audit_log_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let cell = AuditedCell::new(String::from("a"));
+
+    cell.borrow_mut(&mut token).push('b');
+    let line = line!() - 1;
+    assert_eq!(cell.borrow(&token), "ab");
+
+    let log = token.audit_log();
+    assert_eq!(log.len(), 2);
+    assert_eq!(log[0].kind, AccessKind::BorrowMut);
+    assert_eq!(log[0].location.line(), line);
+    assert_eq!(log[1].kind, AccessKind::Borrow);
+
+    token.clear_audit_log();
+    assert!(token.audit_log().is_empty());
+}
@@ -0,0 +1,162 @@
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+const BITS: usize = u64::BITS as usize;
+
+/// A fixed-size, token-gated bitset: bits are read with a `&Token` and changed with a
+/// `&mut Token`, so one set of flags can be shared by every system that needs it. Bulk operations
+/// work a 64-bit word at a time.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, bitset::BitCell};
+/// let (mut token, _) = first().unwrap().token();
+/// let alive = BitCell::new(100);
+/// let visible = BitCell::new(100);
+///
+/// alive.set_bit(&mut token, 3);
+/// alive.set_bit(&mut token, 70);
+/// visible.set_bit(&mut token, 70);
+///
+/// alive.intersect_with(&visible, &mut token);
+/// assert!(!alive.get(3, &token));
+/// assert_eq!(alive.iter_ones(&token).collect::<Vec<_>>(), [70]);
+/// ```
+pub struct BitCell<const ID: usize> {
+    words: Box<[Cell<u64, ID>]>,
+    len: usize,
+}
+
+impl<const ID: usize> BitCell<ID> {
+    /// Creates a bitset of `len` bits, all cleared.
+    pub fn new(len: usize) -> Self {
+        Self {
+            words: (0..len.div_ceil(BITS)).map(|_| Cell::new(0)).collect(),
+            len,
+        }
+    }
+
+    /// The number of bits.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    fn locate(&self, bit: usize) -> (&Cell<u64, ID>, u64) {
+        assert!(bit < self.len, "bit {bit} out of range for a BitCell of {} bits", self.len);
+        (&self.words[bit / BITS], 1 << (bit % BITS))
+    }
+
+    /// # Panics
+    /// Panics if `bit` is out of range.
+    pub fn get<U>(&self, bit: usize, token: &TokenWith<U, ID>) -> bool {
+        let (word, mask) = self.locate(bit);
+        word.get_copy(token) & mask != 0
+    }
+
+    /// # Panics
+    /// Panics if `bit` is out of range.
+    pub fn set_bit<U>(&self, token: &mut TokenWith<U, ID>, bit: usize) {
+        let (word, mask) = self.locate(bit);
+        *word.borrow_mut(token) |= mask;
+    }
+
+    /// # Panics
+    /// Panics if `bit` is out of range.
+    pub fn clear_bit<U>(&self, token: &mut TokenWith<U, ID>, bit: usize) {
+        let (word, mask) = self.locate(bit);
+        *word.borrow_mut(token) &= !mask;
+    }
+
+    /// # Panics
+    /// Panics if `bit` is out of range.
+    pub fn toggle_bit<U>(&self, token: &mut TokenWith<U, ID>, bit: usize) {
+        let (word, mask) = self.locate(bit);
+        *word.borrow_mut(token) ^= mask;
+    }
+
+    pub fn count_ones<U>(&self, token: &TokenWith<U, ID>) -> usize {
+        self.words.iter().map(|word| word.get_copy(token).count_ones() as usize).sum()
+    }
+
+    /// Iterates over the positions of the set bits, in increasing order.
+    pub fn iter_ones<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> impl Iterator<Item = usize> + 'a {
+        self.words.iter().enumerate().flat_map(move |(i, word)| {
+            let mut word = word.get_copy(token);
+            std::iter::from_fn(move || {
+                (word != 0).then(|| {
+                    let bit = word.trailing_zeros() as usize;
+                    word &= word - 1;
+                    i * BITS + bit
+                })
+            })
+        })
+    }
+
+    /// The underlying words, least significant bit first. Bits past [Self::len] are always 0.
+    pub fn words<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> impl Iterator<Item = u64> + 'a {
+        self.words.iter().map(|word| word.get_copy(token))
+    }
+
+    pub fn set_all<U>(&self, token: &mut TokenWith<U, ID>) {
+        self.words.iter().for_each(|word| word.set(token, u64::MAX));
+
+        if let (Some(last), rem @ 1..) = (self.words.last(), self.len % BITS) {
+            last.set(token, (1 << rem) - 1);
+        }
+    }
+
+    pub fn clear_all<U>(&self, token: &mut TokenWith<U, ID>) {
+        self.words.iter().for_each(|word| word.set(token, 0));
+    }
+
+    fn zip_with<U>(&self, other: &Self, token: &mut TokenWith<U, ID>, f: impl Fn(u64, u64) -> u64) {
+        assert_eq!(self.len, other.len, "BitCells must have the same length");
+
+        for (word, other) in self.words.iter().zip(other.words.iter()) {
+            let value = f(word.get_copy(token), other.get_copy(token));
+            word.set(token, value);
+        }
+    }
+
+    /// Sets every bit that is set in `other`.
+    ///
+    /// # Panics
+    /// Panics if the bitsets have different lengths.
+    pub fn union_with<U>(&self, other: &Self, token: &mut TokenWith<U, ID>) {
+        self.zip_with(other, token, |a, b| a | b)
+    }
+
+    /// Clears every bit that isn't set in `other`.
+    ///
+    /// # Panics
+    /// Panics if the bitsets have different lengths.
+    pub fn intersect_with<U>(&self, other: &Self, token: &mut TokenWith<U, ID>) {
+        self.zip_with(other, token, |a, b| a & b)
+    }
+
+    /// Clears every bit that is set in `other`.
+    ///
+    /// # Panics
+    /// Panics if the bitsets have different lengths.
+    pub fn difference_with<U>(&self, other: &Self, token: &mut TokenWith<U, ID>) {
+        self.zip_with(other, token, |a, b| a & !b)
+    }
+}
+
+#[test]
+fn bitset_set_all_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let bits = BitCell::new(70);
+
+    bits.set_all(&mut token);
+    assert_eq!(bits.count_ones(&token), 70);
+
+    bits.difference_with(&bits, &mut token);
+    assert_eq!(bits.count_ones(&token), 0);
+}
@@ -0,0 +1,128 @@
+use std::fmt::Debug;
+#[cfg(not(feature = "allocator-api"))]
+use std::marker::PhantomData;
+
+use crate::alloc::{Allocator, Global};
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// An owning heap pointer whose contents are gated by a token: reading requires a `&Token`,
+/// writing a `&mut Token`, but dropping requires nothing. The value never moves, so
+/// [Self::as_ptr] stays valid for as long as the `TokenBox` is alive, which makes it a building
+/// block for intrusive structures.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, boxed::TokenBox};
+/// let (mut token, _) = first().unwrap().token();
+/// let node = TokenBox::new(String::from("a"));
+/// let address = node.as_ptr();
+///
+/// let moved = node;
+/// moved.borrow_mut(&mut token).push('b');
+///
+/// assert_eq!(moved.borrow(&token), "ab");
+/// assert_eq!(moved.as_ptr(), address);
+/// ```
+#[repr(transparent)]
+pub struct TokenBox<T, const ID: usize, A: Allocator = Global> {
+    #[cfg(feature = "allocator-api")]
+    inner: Box<Cell<T, ID>, A>,
+    #[cfg(not(feature = "allocator-api"))]
+    inner: Box<Cell<T, ID>>,
+    #[cfg(not(feature = "allocator-api"))]
+    _alloc: PhantomData<A>,
+}
+
+impl<T, const ID: usize> TokenBox<T, ID> {
+    pub fn new(t: T) -> Self {
+        Self::from_box(Box::new(t))
+    }
+}
+
+#[cfg(feature = "allocator-api")]
+impl<T, const ID: usize, A: Allocator> TokenBox<T, ID, A> {
+    /// Like [Self::new], but allocates in `alloc` instead of the global allocator.
+    ///
+    /// # Example
+    /// ```rust
+    /// #![feature(allocator_api)]
+    /// # use frankencell::{first, boxed::TokenBox};
+    /// use std::alloc::System;
+    ///
+    /// let (token, _) = first().unwrap().token();
+    /// let node = TokenBox::new_in(1, System);
+    /// assert_eq!(node.borrow(&token), &1);
+    /// ```
+    pub fn new_in(t: T, alloc: A) -> Self {
+        Self {
+            inner: Box::new_in(Cell::new(t), alloc),
+        }
+    }
+
+    pub fn from_box(b: Box<T, A>) -> Self {
+        let (ptr, alloc) = Box::into_raw_with_allocator(b);
+        Self {
+            inner: unsafe {Box::from_raw_in(ptr as *mut Cell<T, ID>, alloc)},
+        }
+    }
+
+    pub fn into_box(self) -> Box<T, A> {
+        let (ptr, alloc) = Box::into_raw_with_allocator(self.inner);
+        unsafe {Box::from_raw_in(ptr as *mut T, alloc)}
+    }
+}
+
+#[cfg(not(feature = "allocator-api"))]
+impl<T, const ID: usize, A: Allocator> TokenBox<T, ID, A> {
+    pub fn from_box(b: Box<T>) -> Self {
+        Self {
+            inner: unsafe {Box::from_raw(Box::into_raw(b) as *mut Cell<T, ID>)},
+            _alloc: PhantomData,
+        }
+    }
+
+    pub fn into_box(self) -> Box<T> {
+        unsafe {Box::from_raw(Box::into_raw(self.inner) as *mut T)}
+    }
+}
+
+impl<T, const ID: usize, A: Allocator> TokenBox<T, ID, A> {
+    pub fn into_inner(self) -> T {
+        *self.into_box()
+    }
+
+    pub fn as_ptr(&self) -> *const T {
+        self.inner.as_ptr()
+    }
+
+    pub fn as_cell(&self) -> &Cell<T, ID> {
+        &self.inner
+    }
+
+    pub fn borrow<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.inner.borrow(token)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn borrow_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        self.inner.borrow_mut(token)
+    }
+
+    /// Owning the `TokenBox` mutably already proves no other borrows exist.
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+}
+
+impl<T: Default, const ID: usize> Default for TokenBox<T, ID> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, const ID: usize, A: Allocator> Debug for TokenBox<T, ID, A> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TokenBox<{}, {}>", std::any::type_name::<T>(), ID)
+    }
+}
@@ -0,0 +1,243 @@
+//! An alternative branding backend, where a family is a zero-sized marker type declared with
+//! [brand!] instead of a `const ID: usize`. Marker types need no const-generic arithmetic to keep
+//! apart, and there's no limit on how many can be declared, so generic code over them is written
+//! like any other generic code.
+//!
+//! [brand!]: crate::brand!
+
+use std::cell::UnsafeCell;
+use std::fmt::Debug;
+use std::marker::PhantomData;
+
+use crate::cells::Cell;
+use crate::sync::Ordering;
+use crate::tokens::Token;
+
+// The flag `brand!` declares, so the macro goes through the crate's atomics
+#[doc(hidden)]
+pub use crate::sync::statics::AtomicBool;
+
+/// A family marker type, implemented by [brand!](crate::brand!) types and [FamilyBrand].
+///
+/// # Safety
+/// [Self::claim] must only succeed while no [BrandToken] of this brand exists.
+pub unsafe trait Brand: 'static {
+    /// Marks this brand's [BrandToken] as existing, or returns false if it already does.
+    fn claim() -> bool;
+
+    /// Called when this brand's [BrandToken] is dropped.
+    fn release();
+}
+
+// Used by the `Brand` impls `brand!` generates
+#[doc(hidden)]
+pub fn claim(claimed: &AtomicBool) -> bool {
+    claimed.compare_exchange(false, true, Ordering::Acquire, Ordering::Relaxed).is_ok()
+}
+
+#[doc(hidden)]
+pub fn release(claimed: &AtomicBool) {
+    claimed.store(false, Ordering::Release);
+}
+
+/// The token for brand `B`. At most one exists at a time; dropping it lets a new one be claimed.
+pub struct BrandToken<B: Brand> {
+    _brand: PhantomData<fn() -> B>,
+}
+
+impl<B: Brand> BrandToken<B> {
+    /// Claims `B`'s token, or returns `None` if it already exists.
+    pub fn new() -> Option<Self> {
+        B::claim().then_some(Self { _brand: PhantomData })
+    }
+
+    pub const fn cell<T>(&self, t: T) -> BrandCell<T, B> {
+        BrandCell::new(t)
+    }
+}
+
+impl<B: Brand> Drop for BrandToken<B> {
+    fn drop(&mut self) {
+        B::release();
+    }
+}
+
+impl<B: Brand> Debug for BrandToken<B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BrandToken<{}>", std::any::type_name::<B>())
+    }
+}
+
+/// A [Cell](crate::Cell) whose family is the brand `B`, and whose value is borrowed through `B`'s
+/// [BrandToken].
+///
+/// # Example
+/// ```rust
+/// use frankencell::brand;
+/// use frankencell::brand::{BrandCell, BrandToken};
+///
+/// brand!(Scene);
+/// brand!(Physics);
+///
+/// let mut scene = BrandToken::<Scene>::new().unwrap();
+/// let physics = BrandToken::<Physics>::new().unwrap();
+/// assert!(BrandToken::<Scene>::new().is_none());
+///
+/// let names = BrandCell::new(vec!["camera"]);
+/// let gravity: BrandCell<f32, Physics> = BrandCell::new(9.81);
+///
+/// names.borrow_mut(&mut scene).push("light");
+/// assert_eq!(names.borrow(&scene).len(), 2);
+/// assert_eq!(*gravity.borrow(&physics), 9.81);
+/// ```
+///
+/// Cells can't be borrowed with another brand's token:
+/// ```compile_fail
+/// # use frankencell::brand;
+/// # use frankencell::brand::{BrandCell, BrandToken};
+/// brand!(Scene);
+/// brand!(Physics);
+///
+/// let physics = BrandToken::<Physics>::new().unwrap();
+/// let names: BrandCell<_, Scene> = BrandCell::new(vec!["camera"]);
+/// names.borrow(&physics);
+/// ```
+#[derive(Default)]
+#[repr(transparent)]
+pub struct BrandCell<T, B: Brand> {
+    inner: UnsafeCell<T>,
+    _brand: PhantomData<fn() -> B>,
+}
+
+unsafe impl<T: Send, B: Brand> Send for BrandCell<T, B> {}
+unsafe impl<T: Send + Sync, B: Brand> Sync for BrandCell<T, B> {}
+
+impl<T, B: Brand> Debug for BrandCell<T, B> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "BrandCell<{}, {}>", std::any::type_name::<T>(), std::any::type_name::<B>())
+    }
+}
+
+impl<T, B: Brand> BrandCell<T, B> {
+    pub const fn new(t: T) -> Self {
+        Self { inner: UnsafeCell::new(t), _brand: PhantomData }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+
+    pub fn borrow<'a>(&'a self, _: &'a BrandToken<B>) -> &'a T {
+        // Safety: the token is shared for as long as the reference lives, so no `&mut T` exists
+        unsafe {&*self.inner.get()}
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn borrow_mut<'a>(&'a self, _: &'a mut BrandToken<B>) -> &'a mut T {
+        // Safety: the token is mutably borrowed for as long as the reference lives
+        unsafe {&mut *self.inner.get()}
+    }
+}
+
+/// The brand of usize-ID family `ID`, for handing a family over to code written against the
+/// marker-brand backend. Its token can't be claimed with [BrandToken::new]; it's only minted by
+/// giving up the family's [Token] with [Token::into_brand], so the two backends never both have a
+/// token for the same cells.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell};
+/// use frankencell::brand::{Brand, BrandCell, BrandToken};
+///
+/// // A library written against the marker-brand backend
+/// fn bump<B: Brand>(counter: &BrandCell<u32, B>, token: &mut BrandToken<B>) {
+///     *counter.borrow_mut(token) += 1;
+/// }
+///
+/// let (token, _) = first().unwrap().token();
+/// let counter = Cell::new(0);
+/// let other = Cell::new(10);
+///
+/// let mut token = token.into_brand();
+/// bump(counter.as_brand(), &mut token);
+/// bump(other.as_brand(), &mut token);
+///
+/// assert_eq!(*counter.as_brand().borrow(&token), 1);
+/// assert_eq!(other.into_brand().into_inner(), 11);
+/// ```
+#[derive(Clone, Copy, Debug)]
+pub struct FamilyBrand<const ID: usize>;
+
+unsafe impl<const ID: usize> Brand for FamilyBrand<ID> {
+    fn claim() -> bool {
+        false
+    }
+
+    fn release() {}
+}
+
+impl<const ID: usize> Token<ID> {
+    /// Gives up this token for the marker-brand token of the same family. There's no way back, so
+    /// every cell in the family is owned by the new token from then on.
+    pub fn into_brand(self) -> BrandToken<FamilyBrand<ID>> {
+        BrandToken { _brand: PhantomData }
+    }
+}
+
+impl<T, const ID: usize> Cell<T, ID> {
+    /// Converts this cell into the marker-brand backend's cell of the same family.
+    pub fn into_brand(self) -> BrandCell<T, FamilyBrand<ID>> {
+        BrandCell::new(self.into_inner())
+    }
+
+    /// Views this cell as the marker-brand backend's cell of the same family, which can only be
+    /// borrowed with the token from [Token::into_brand].
+    pub fn as_brand(&self) -> &BrandCell<T, FamilyBrand<ID>> {
+        // Safety: both are `repr(transparent)` over an `UnsafeCell<T>`, and once the family's
+        // token has been converted, the brand token is the only way to borrow either
+        unsafe {&*(self as *const Self as *const BrandCell<T, FamilyBrand<ID>>)}
+    }
+}
+
+/// Declares a zero-sized [Brand](crate::brand::Brand) marker type, whose
+/// [BrandToken](crate::brand::BrandToken) can be claimed once at a time.
+#[macro_export]
+macro_rules! brand {
+    ($(#[$attr:meta])* $vis:vis $name:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Debug)]
+        $vis struct $name;
+
+        const _: () = {
+            static CLAIMED: $crate::brand::AtomicBool = $crate::brand::AtomicBool::new(false);
+
+            unsafe impl $crate::brand::Brand for $name {
+                fn claim() -> bool {
+                    $crate::brand::claim(&CLAIMED)
+                }
+
+                fn release() {
+                    $crate::brand::release(&CLAIMED)
+                }
+            }
+        };
+    }
+}
+
+#[test]
+fn brand_token_release_test() {
+    brand!(Test);
+
+    let token = BrandToken::<Test>::new().unwrap();
+    assert!(BrandToken::<Test>::new().is_none());
+    drop(token);
+
+    let mut token = BrandToken::<Test>::new().unwrap();
+    let cell = token.cell(1);
+    *cell.borrow_mut(&mut token) += 1;
+    assert_eq!(cell.into_inner(), 2);
+}
@@ -0,0 +1,181 @@
+use std::sync::Arc;
+
+use crate::cells::Cell;
+use crate::sync::{AtomicUsize, Ordering, UnsafeCell};
+use crate::tokens::TokenWith;
+
+/// Two copies of a value: readers see the front one with a `&Token`, the updater writes the back
+/// one with a `&mut Token`, and [Self::swap] flips them once the update is done. The usual shape
+/// of a frame-based update loop, where each frame's state is computed from the previous one.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, buffer::DoubleBuffer};
+/// let (mut token, _) = first().unwrap().token();
+/// let positions = DoubleBuffer::new(vec![0, 10], vec![0, 0]);
+///
+/// for _ in 0..3 {
+///     let (current, next) = positions.front_and_back_mut(&mut token);
+///     for (next, current) in next.iter_mut().zip(current) {
+///         *next = current + 1;
+///     }
+///     positions.swap(&mut token);
+/// }
+///
+/// assert_eq!(positions.front(&token), &[3, 13]);
+/// ```
+pub struct DoubleBuffer<T, const ID: usize> {
+    buffers: [Cell<T, ID>; 2],
+    front: Cell<usize, ID>,
+}
+
+impl<T: Default, const ID: usize> Default for DoubleBuffer<T, ID> {
+    fn default() -> Self {
+        Self::new(T::default(), T::default())
+    }
+}
+
+impl<T, const ID: usize> DoubleBuffer<T, ID> {
+    pub const fn new(front: T, back: T) -> Self {
+        Self {
+            buffers: [Cell::new(front), Cell::new(back)],
+            front: Cell::new(0),
+        }
+    }
+
+    /// Returns the front and back values, in that order.
+    pub fn into_inner(self) -> (T, T) {
+        let front = self.front.into_inner();
+        let [a, b] = self.buffers.map(Cell::into_inner);
+        if front == 0 { (a, b) } else { (b, a) }
+    }
+
+    fn index<U>(&self, token: &TokenWith<U, ID>) -> usize {
+        self.front.get_copy(token)
+    }
+
+    pub fn front<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.buffers[self.index(token)].borrow(token)
+    }
+
+    pub fn back<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.buffers[1 - self.index(token)].borrow(token)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn back_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        let back = 1 - self.index(token);
+        self.buffers[back].borrow_mut(token)
+    }
+
+    /// Reads the front value while writing the back one.
+    #[allow(clippy::mut_from_ref)]
+    pub fn front_and_back_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> (&'a T, &'a mut T) {
+        let front = self.index(token);
+        let _ = token;
+
+        // Safety: the two buffers are different cells, and the token is mutably borrowed for `'a`
+        unsafe {(&*self.buffers[front].inner.get(), &mut *self.buffers[1 - front].inner.get())}
+    }
+
+    /// Makes the back value the front one, and vice versa.
+    pub fn swap<U>(&self, token: &mut TokenWith<U, ID>) {
+        *self.front.borrow_mut(token) ^= 1;
+    }
+}
+
+// Set in `TripleShared::middle` when the middle buffer holds a snapshot the reader hasn't seen
+const FRESH: usize = 0b100;
+const INDEX: usize = 0b011;
+
+struct TripleShared<T> {
+    buffers: [UnsafeCell<T>; 3],
+    middle: AtomicUsize,
+}
+
+// Safety: each buffer is only ever owned by one side at a time: the writer's back buffer, the
+// reader's front buffer, or the middle one that neither touches until they swap it out
+unsafe impl<T: Send> Send for TripleShared<T> {}
+unsafe impl<T: Send> Sync for TripleShared<T> {}
+
+/// A wait-free way to hand snapshots to a real-time thread. The writer fills in the back buffer
+/// with the family's token and [publishes](Self::publish) it, and the [TripleReader] always gets
+/// the most recently published snapshot; neither side ever waits for the other.
+///
+/// After publishing, the back buffer holds an older snapshot, so the writer should overwrite it
+/// completely rather than update it in place.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, buffer::TripleBuffer};
+/// let (mut token, _) = first().unwrap().token();
+/// let (buffer, mut reader) = TripleBuffer::new(0u64);
+///
+/// std::thread::scope(|s| {
+///     s.spawn(move || {
+///         let mut last = 0;
+///         while last < 1000 {
+///             let latest = *reader.read();
+///             assert!(latest >= last);
+///             last = latest;
+///         }
+///     });
+///
+///     for frame in 1..=1000 {
+///         *buffer.write(&mut token) = frame;
+///         buffer.publish(&mut token);
+///     }
+/// });
+/// ```
+pub struct TripleBuffer<T, const ID: usize> {
+    shared: Arc<TripleShared<T>>,
+    back: Cell<usize, ID>,
+}
+
+/// The reading end of a [TripleBuffer].
+pub struct TripleReader<T> {
+    shared: Arc<TripleShared<T>>,
+    front: usize,
+}
+
+impl<T, const ID: usize> TripleBuffer<T, ID> {
+    /// Creates a buffer whose every snapshot starts out as a clone of `value`.
+    pub fn new(value: T) -> (Self, TripleReader<T>) where T: Clone {
+        let shared = Arc::new(TripleShared {
+            buffers: [UnsafeCell::new(value.clone()), UnsafeCell::new(value.clone()), UnsafeCell::new(value)],
+            middle: AtomicUsize::new(1),
+        });
+
+        let reader = TripleReader { shared: Arc::clone(&shared), front: 0 };
+        (Self { shared, back: Cell::new(2) }, reader)
+    }
+
+    /// The back buffer, to be filled in before the next [Self::publish].
+    #[allow(clippy::mut_from_ref)]
+    pub fn write<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        let back = self.back.get_copy(token);
+        let _ = token;
+
+        // Safety: only the writer accesses the back buffer, and the token is mutably borrowed
+        self.shared.buffers[back].with_mut(|value| unsafe {&mut *value})
+    }
+
+    /// Hands the back buffer over to the reader as its latest snapshot.
+    pub fn publish<U>(&self, token: &mut TokenWith<U, ID>) {
+        let back = self.back.borrow_mut(token);
+        *back = self.shared.middle.swap(*back | FRESH, Ordering::AcqRel) & INDEX;
+    }
+}
+
+impl<T> TripleReader<T> {
+    /// The latest published snapshot, or the previous one read if nothing new has been published
+    /// since.
+    pub fn read(&mut self) -> &T {
+        if self.shared.middle.load(Ordering::Relaxed) & FRESH != 0 {
+            self.front = self.shared.middle.swap(self.front, Ordering::AcqRel) & INDEX;
+        }
+
+        // Safety: only the reader accesses the front buffer
+        self.shared.buffers[self.front].with_mut(|value| unsafe {&*value})
+    }
+}
@@ -1,3 +1,4 @@
+use std::fmt::Debug;
 use std::marker::PhantomData;
 
 use crate::tokens::*;
@@ -29,4 +30,22 @@ impl<const ID: usize> TokenBuilder<ID> {
         unsafe {(TokenWith::new(u),
                  TokenBuilder::new())}
     }
+
+    /// The ID of the token this builder will create.
+    pub const fn id(&self) -> usize {
+        ID
+    }
+}
+
+impl<const ID: usize> Debug for TokenBuilder<ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "TokenBuilder<{}>", ID)
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<const ID: usize> defmt::Format for TokenBuilder<ID> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "TokenBuilder<{}>", ID)
+    }
 }
@@ -0,0 +1,79 @@
+use std::cell::RefCell;
+
+use crate::cells::Cell;
+
+/// A typed bump allocator whose allocations live as long as the allocator itself. Since
+/// [Self::alloc] only needs `&self`, any number of values can be allocated while earlier ones are
+/// still borrowed, which makes it easy to build graphs whose nodes point at each other with plain
+/// references. The values are [Cell]s, so they can still be mutated with the family's token.
+///
+/// Values are dropped when the `TokenBump` is.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, bump::TokenBump};
+/// struct Node<'a, const ID: usize> {
+///     value: u32,
+///     next: Option<&'a Cell<Node<'a, ID>, ID>>,
+/// }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let bump = TokenBump::new();
+///
+/// let a = bump.alloc(Node { value: 1, next: None });
+/// let b = bump.alloc(Node { value: 2, next: Some(a) });
+/// a.borrow_mut(&mut token).next = Some(b);
+///
+/// let next = a.borrow(&token).next.unwrap();
+/// assert_eq!(next.borrow(&token).value, 2);
+/// ```
+pub struct TokenBump<T, const ID: usize> {
+    chunks: RefCell<Vec<Vec<Cell<T, ID>>>>,
+}
+
+const FIRST_CHUNK: usize = 16;
+
+impl<T, const ID: usize> Default for TokenBump<T, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const ID: usize> TokenBump<T, ID> {
+    pub const fn new() -> Self {
+        Self {
+            chunks: RefCell::new(Vec::new()),
+        }
+    }
+
+    pub fn alloc(&self, value: T) -> &Cell<T, ID> {
+        let mut chunks = self.chunks.borrow_mut();
+
+        let full = chunks.last().is_none_or(|chunk| chunk.len() == chunk.capacity());
+        if full {
+            let capacity = chunks.last().map_or(FIRST_CHUNK, |chunk| chunk.capacity() * 2);
+            chunks.push(Vec::with_capacity(capacity));
+        }
+
+        let chunk = chunks.last_mut().unwrap();
+        chunk.push(Cell::new(value));
+
+        // Safety: chunks are never pushed to beyond their capacity, so their contents never move,
+        // and nothing is removed until `self` is dropped
+        unsafe {&*(chunk.last().unwrap() as *const Cell<T, ID>)}
+    }
+
+    /// Number of values allocated so far.
+    pub fn len(&self) -> usize {
+        self.chunks.borrow().iter().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Takes every value back out, in allocation order.
+    pub fn into_vec(self) -> Vec<T> {
+        self.chunks.into_inner().into_iter().flatten().map(Cell::into_inner).collect()
+    }
+}
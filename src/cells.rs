@@ -1,5 +1,6 @@
 use std::{cell::UnsafeCell, fmt::Debug, any::Any};
 
+use crate::paranoid;
 use crate::tokens::TokenWith;
 
 /// A value whose ownership is tied to a [TokenWith], usually a
@@ -19,7 +20,14 @@ use crate::tokens::TokenWith;
 //TODO: More cell types. Currently, Token and Cell have a one-to-many relationship, but it may be
 //useful in the future to create a token/cell with a many-to-one relationship such as in
 //exaples/arena.rs
+//
+// With the `zerocopy` feature, a `Cell<T, ID>` implements zerocopy's `FromBytes`, `IntoBytes`,
+// `Unaligned` and `KnownLayout` whenever `T` does, so cell-typed views can be overlaid onto
+// (mutable) byte buffers.
 #[derive(Default)]
+#[cfg_attr(feature = "zerocopy", derive(
+    zerocopy::FromBytes, zerocopy::IntoBytes, zerocopy::Unaligned, zerocopy::KnownLayout,
+))]
 #[repr(transparent)]
 pub struct Cell<T, const ID: usize> {
     pub(crate) inner: UnsafeCell<T>,
@@ -56,12 +64,75 @@ impl<T, const ID: usize> Cell<T, ID> {
         }
     }
 
+    /// Like [Self::new], but statically rejects values containing a token. See [NoSelfToken].
+    ///
+    /// [NoSelfToken]: crate::tokens::NoSelfToken
+    #[cfg(feature = "no-self-token")]
+    pub const fn new_checked(t: T) -> Self where T: crate::tokens::NoSelfToken {
+        Self::new(t)
+    }
+
     /// Reinterpret a `&mut T` into a `&mut Self`. This may be useful if you only need to
     /// temporarily attach a value to a token, for example in a closure.
     pub fn from_mut(m: &mut T) -> &mut Self {
         unsafe {std::mem::transmute(m)}
     }
 
+    /// Reinterpret a `Box<T>` as a `Box<Self>` without reallocating.
+    pub fn from_box(b: Box<T>) -> Box<Self> {
+        unsafe {Box::from_raw(Box::into_raw(b) as *mut Self)}
+    }
+
+    /// Reinterpret a `Box<Self>` as a `Box<T>` without reallocating. Since this consumes the box,
+    /// no outstanding borrows can exist.
+    pub fn into_box(b: Box<Self>) -> Box<T> {
+        unsafe {Box::from_raw(Box::into_raw(b) as *mut T)}
+    }
+
+    /// Reinterpret a `Vec<T>` as a `Vec<Self>`, reusing the allocation.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let samples = vec![0.5f32; 4096];
+    /// let ptr = samples.as_ptr();
+    ///
+    /// let cells = Cell::<_, 0>::wrap_vec(samples);
+    /// *cells[0].borrow_mut(&mut token) = 1.0;
+    ///
+    /// let samples = Cell::unwrap_vec(cells);
+    /// assert_eq!(samples.as_ptr(), ptr);
+    /// assert_eq!(samples[..2], [1.0, 0.5]);
+    /// ```
+    pub fn wrap_vec(v: Vec<T>) -> Vec<Self> {
+        let mut v = std::mem::ManuallyDrop::new(v);
+        // Safety: `Self` is `repr(transparent)` over `T`, so the layout is unchanged
+        unsafe {Vec::from_raw_parts(v.as_mut_ptr() as *mut Self, v.len(), v.capacity())}
+    }
+
+    /// Reinterpret a `Vec<Self>` as a `Vec<T>`, reusing the allocation. Since this consumes the
+    /// vector, no outstanding borrows can exist.
+    pub fn unwrap_vec(v: Vec<Self>) -> Vec<T> {
+        let mut v = std::mem::ManuallyDrop::new(v);
+        unsafe {Vec::from_raw_parts(v.as_mut_ptr() as *mut T, v.len(), v.capacity())}
+    }
+
+    /// Moves `t` onto the heap and leaks it, for long-lived nodes that don't belong in an arena.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let node: &'static Cell<_, 0> = Cell::leak(vec![1]);
+    ///
+    /// node.borrow_mut(&mut token).push(2);
+    /// assert_eq!(node.borrow(&token), &[1, 2]);
+    /// ```
+    pub fn leak(t: T) -> &'static Self where T: 'static {
+        Box::leak(Box::new(Self::new(t)))
+    }
+
     pub fn into_inner(self) -> T {
         self.inner.into_inner()
     }
@@ -70,29 +141,44 @@ impl<T, const ID: usize> Cell<T, ID> {
         self.inner.get()
     }
 
+    /// The ID of the family this cell belongs to.
+    pub const fn id(&self) -> usize {
+        ID
+    }
+
+    /// Whether both references point to the same cell, without looking at the contents.
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        std::ptr::eq(self, other)
+    }
+
     /// Reinterpret a `&self` as a `&T`
     ///
     /// # Safety
     /// Since `Cell` depends on the interior mutability pattern, using this function without
     /// passing the appropriate token to prove ownership could result in aliased mutability.
     ///
-    /// ```
+    /// ```no_run
     /// # use frankencell::{first, Cell};
     /// let (mut token, _) = first().unwrap().token();
     /// let cell = Cell::new(String::from("Hello"));
     ///
     /// let cell_mut = cell.borrow_mut(&mut token);
+    /// // Undefined behavior: `cell_mut` is still live
     /// let cell_ref = unsafe {cell.get()};
     ///
     /// drop(cell_mut);
     /// drop(cell_ref);
     /// ```
+    ///
+    /// With the `paranoid` feature enabled, this panics in debug builds if a token borrow of this
+    /// cell may still be live.
     pub unsafe fn get(&self) -> &T {
+        paranoid::check_shared(ID, self.as_ptr() as usize);
         unsafe {std::mem::transmute(self)}
     }
 
     /// Reinterpret a `&mut self` as a `&mut T`. 
-    pub fn get_mut(&mut self) -> &mut T {
+    pub const fn get_mut(&mut self) -> &mut T {
         unsafe {std::mem::transmute(self)}
     }
 
@@ -108,7 +194,10 @@ impl<T, const ID: usize> Cell<T, ID> {
     /// println!("{}", cell_cell.borrow(&token).borrow(&token));
     /// 
     /// ```
-    pub fn borrow<U>(&self, _: &TokenWith<U, ID>) -> &T {
+    pub fn borrow<'a, U>(&'a self, _: &'a TokenWith<U, ID>) -> &'a T {
+        paranoid::on_borrow(ID);
+        #[cfg(all(feature = "tracing", debug_assertions))]
+        tracing::trace!(family = ID, cell = ?self.as_ptr(), "borrow");
         unsafe {self.inner.get().as_ref().unwrap_unchecked()}
     }
 
@@ -127,7 +216,394 @@ impl<T, const ID: usize> Cell<T, ID> {
     ///
     /// println!("{}", safe_ref.borrow(&token));
     /// ```
-    pub fn borrow_mut<U>(&self, _: &mut TokenWith<U, ID>) -> &mut T {
+    ///
+    /// The returned reference keeps the token borrowed, so it can't be used to create a second
+    /// `&mut T`:
+    /// ```compile_fail
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let cell = Cell::new(0);
+    ///
+    /// let a = cell.borrow_mut(&mut token);
+    /// let b = cell.borrow_mut(&mut token);
+    /// *a += *b;
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn borrow_mut<'a, U>(&'a self, _: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        paranoid::on_borrow_mut(ID, self.as_ptr() as usize);
+        #[cfg(all(feature = "tracing", debug_assertions))]
+        tracing::trace!(family = ID, cell = ?self.as_ptr(), "borrow_mut");
         unsafe {self.inner.get().as_mut().unwrap_unchecked()}
     }
+
+    /// Like [Self::borrow], but usable in `const fn`s and const items, so that data structures
+    /// built out of cells can be evaluated at compile time. Borrows made this way aren't seen by
+    /// the `paranoid` and `tracing` features.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{Cell, Token};
+    /// static POWERS: [Cell<u32, 1000>; 5] = {
+    ///     // Safety: this token only exists while the static is being evaluated
+    ///     let mut token = unsafe {Token::<1000>::new(())};
+    ///     let powers = [const { Cell::new(1) }; 5];
+    ///
+    ///     let mut i = 1;
+    ///     while i < powers.len() {
+    ///         *powers[i].borrow_mut_const(&mut token) = *powers[i - 1].borrow_const(&token) * 2;
+    ///         i += 1;
+    ///     }
+    ///     powers
+    /// };
+    /// ```
+    pub const fn borrow_const<'a, U>(&'a self, _: &'a TokenWith<U, ID>) -> &'a T {
+        unsafe {&*self.inner.get()}
+    }
+
+    /// Like [Self::borrow_mut], but usable in `const fn`s and const items. See
+    /// [Self::borrow_const].
+    #[allow(clippy::mut_from_ref)]
+    pub const fn borrow_mut_const<'a, U>(&'a self, _: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        unsafe {&mut *self.inner.get()}
+    }
+
+    /// Copy the value out of the cell, mirroring [std::cell::Cell::get].
+    ///
+    /// # Example
+    /// ```
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let counter = Cell::new(0u32);
+    ///
+    /// let next = counter.get_copy(&token) + 1;
+    /// counter.set(&mut token, next);
+    /// assert_eq!(counter.get_copy(&token), 1);
+    /// ```
+    pub fn get_copy<U>(&self, token: &TokenWith<U, ID>) -> T where T: Copy {
+        *self.borrow(token)
+    }
+
+    /// Clone the value out of the cell, for when an owned value is needed in expression position.
+    ///
+    /// # Example
+    /// ```
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let name = Cell::new(String::from("Ferris"));
+    ///
+    /// let old = name.get_cloned(&token);
+    /// name.borrow_mut(&mut token).push_str(" the crab");
+    /// assert_eq!(old, "Ferris");
+    /// ```
+    pub fn get_cloned<U>(&self, token: &TokenWith<U, ID>) -> T where T: Clone {
+        self.borrow(token).clone()
+    }
+
+    /// Overwrite the value in the cell, mirroring [std::cell::Cell::set].
+    pub fn set<U>(&self, token: &mut TokenWith<U, ID>, value: T) {
+        *self.borrow_mut(token) = value;
+    }
+
+    /// Replace the value with `new` if it is equal to `expected`, similar to
+    /// [AtomicUsize::compare_exchange](std::sync::atomic::AtomicUsize::compare_exchange). Returns
+    /// the old value on success. On failure, the cell is left untouched and `new` is given back.
+    ///
+    /// # Example
+    /// ```
+    /// # use frankencell::{first, Cell};
+    /// #[derive(Debug, PartialEq)]
+    /// enum State { Idle, Running, Done }
+    ///
+    /// let (mut token, _) = first().unwrap().token();
+    /// let state = Cell::new(State::Idle);
+    ///
+    /// assert_eq!(state.compare_exchange_with(&mut token, &State::Idle, State::Running), Ok(State::Idle));
+    /// assert_eq!(state.compare_exchange_with(&mut token, &State::Idle, State::Done), Err(State::Done));
+    /// assert_eq!(state.borrow(&token), &State::Running);
+    /// ```
+    pub fn compare_exchange_with<U>(&self, token: &mut TokenWith<U, ID>, expected: &T, new: T) -> Result<T, T>
+    where
+        T: PartialEq,
+    {
+        let value = self.borrow_mut(token);
+        if value == expected {
+            Ok(std::mem::replace(value, new))
+        } else {
+            Err(new)
+        }
+    }
+
+    /// Swaps `new` into the cell while `f` runs, then puts the original value back, even if `f`
+    /// panics. `f` is lent the token and given the original value, like a context variable that's
+    /// overridden for one call.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let indent = Cell::new(0);
+    ///
+    /// let nested = indent.scoped_replace(&mut token, 4, |token, outer| {
+    ///     assert_eq!(*outer, 0);
+    ///     *indent.borrow(token)
+    /// });
+    ///
+    /// assert_eq!(nested, 4);
+    /// assert_eq!(*indent.borrow(&token), 0);
+    /// ```
+    pub fn scoped_replace<U, R>(&self, token: &mut TokenWith<U, ID>, new: T, f: impl FnOnce(&mut TokenWith<U, ID>, &T) -> R) -> R {
+        struct Restore<'a, T, const ID: usize> {
+            cell: &'a Cell<T, ID>,
+            old: std::mem::ManuallyDrop<T>,
+        }
+
+        impl<T, const ID: usize> Drop for Restore<'_, T, ID> {
+            fn drop(&mut self) {
+                // Safety: `scoped_replace` holds the token mutably until this runs, and every
+                // borrow lent to `f` has ended by then, returning or unwinding
+                unsafe {*self.cell.inner.get() = std::mem::ManuallyDrop::take(&mut self.old)}
+            }
+        }
+
+        let old = std::mem::replace(self.borrow_mut(token), new);
+        let restore = Restore { cell: self, old: std::mem::ManuallyDrop::new(old) };
+        f(token, &restore.old)
+    }
+
+    /// Moves this cell into family `NEW` in place, without unwrapping and rewrapping the value.
+    /// Both tokens are taken so that handing data over between ownership domains is visible at
+    /// the call site.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell};
+    /// let (mut staging, next) = first().unwrap().token();
+    /// let (mut live, _) = next.token();
+    ///
+    /// let table = Cell::new(vec![0u8; 4096]);
+    /// table.borrow_mut(&mut staging)[0] = 1;
+    ///
+    /// let table = table.rebrand(&mut staging, &mut live);
+    /// assert_eq!(table.borrow(&live)[0], 1);
+    /// ```
+    pub fn rebrand<const NEW: usize, U, V>(self, _: &mut TokenWith<U, ID>, _: &mut TokenWith<V, NEW>) -> Cell<T, NEW> {
+        Cell::new(self.into_inner())
+    }
+
+    /// Lends this cell to family `NEW` for as long as the old family's token is mutably borrowed,
+    /// so nothing in the old family can touch it in the meantime.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell};
+    /// let (mut a, next) = first().unwrap().token();
+    /// let (mut b, _) = next.token();
+    /// let cell = Cell::new(1);
+    ///
+    /// let lent = cell.rebrand_ref(&mut a, &b);
+    /// *lent.borrow_mut(&mut b) += 1;
+    ///
+    /// assert_eq!(cell.borrow(&a), &2);
+    /// ```
+    pub fn rebrand_ref<'a, const NEW: usize, U, V>(&'a self, _: &'a mut TokenWith<U, ID>, _: &TokenWith<V, NEW>) -> &'a Cell<T, NEW> {
+        // Safety: both are `repr(transparent)` over the same `UnsafeCell<T>`, and the old token
+        // stays mutably borrowed for `'a`
+        unsafe {&*(self as *const Self as *const Cell<T, NEW>)}
+    }
+}
+
+impl<T, const ID: usize> From<T> for Cell<T, ID> {
+    fn from(t: T) -> Self {
+        Self::new(t)
+    }
+}
+
+/// Collects into a single cell holding the whole collection. To get a cell per item, see
+/// [CollectCells](crate::iter::CollectCells).
+impl<A, T: FromIterator<A>, const ID: usize> FromIterator<A> for Cell<T, ID> {
+    fn from_iter<I: IntoIterator<Item = A>>(iter: I) -> Self {
+        Self::new(iter.into_iter().collect())
+    }
+}
+
+macro_rules! impl_downcast {
+    ($($any:ty),*) => {$(
+        impl<const ID: usize> Cell<Box<$any>, ID> {
+            /// Borrows the boxed value as a `T`, or returns `None` if it's another type.
+            ///
+            /// # Example
+            /// ```rust
+            /// # use frankencell::{first, Cell};
+            /// # use std::any::Any;
+            /// let (mut token, _) = first().unwrap().token();
+            /// let values: Vec<Cell<Box<dyn Any>, 0>> = vec![Cell::new(Box::new(1u32)), Cell::new(Box::new("two"))];
+            ///
+            /// *values[0].downcast_borrow_mut::<u32>(&mut token).unwrap() += 1;
+            /// assert_eq!(values[0].downcast_borrow::<u32>(&token), Some(&2));
+            /// assert_eq!(values[1].downcast_borrow::<u32>(&token), None);
+            /// ```
+            // The token's payload is an `impl Sized` so that `T` is the only parameter to name
+            pub fn downcast_borrow<'a, T: Any>(&'a self, token: &'a TokenWith<impl Sized, ID>) -> Option<&'a T> {
+                self.borrow(token).downcast_ref()
+            }
+
+            /// Mutably borrows the boxed value as a `T`, or returns `None` if it's another type.
+            #[allow(clippy::mut_from_ref)]
+            pub fn downcast_borrow_mut<'a, T: Any>(&'a self, token: &'a mut TokenWith<impl Sized, ID>) -> Option<&'a mut T> {
+                self.borrow_mut(token).downcast_mut()
+            }
+        }
+    )*}
+}
+
+impl_downcast!(dyn Any, dyn Any + Send, dyn Any + Send + Sync);
+
+/// Compares, hashes and orders a reference to a cell by its address instead of its contents, so
+/// cells can be used as identity keys in maps without a token. Zero-sized cells may share an
+/// address.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, ByAddress, Cell};
+/// # use std::collections::HashSet;
+/// let (token, _) = first().unwrap().token();
+/// let (a, b) = (Cell::new(1), Cell::new(1));
+///
+/// let mut visited = HashSet::new();
+/// assert!(visited.insert(ByAddress(&a)));
+/// assert!(visited.insert(ByAddress(&b)));
+/// assert!(!visited.insert(ByAddress(&a)));
+///
+/// assert!(a.ptr_eq(&a) && !a.ptr_eq(&b));
+/// assert_eq!(a.borrow(&token), b.borrow(&token));
+/// ```
+pub struct ByAddress<'a, T, const ID: usize>(pub &'a Cell<T, ID>);
+
+impl<T, const ID: usize> Clone for ByAddress<'_, T, ID> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const ID: usize> Copy for ByAddress<'_, T, ID> {}
+
+impl<T, const ID: usize> Debug for ByAddress<'_, T, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "ByAddress({:p})", self.0)
+    }
+}
+
+impl<T, const ID: usize> PartialEq for ByAddress<'_, T, ID> {
+    fn eq(&self, other: &Self) -> bool {
+        self.0.ptr_eq(other.0)
+    }
+}
+
+impl<T, const ID: usize> Eq for ByAddress<'_, T, ID> {}
+
+impl<T, const ID: usize> std::hash::Hash for ByAddress<'_, T, ID> {
+    fn hash<H: std::hash::Hasher>(&self, state: &mut H) {
+        std::ptr::hash(self.0, state)
+    }
+}
+
+impl<T, const ID: usize> PartialOrd for ByAddress<'_, T, ID> {
+    fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl<T, const ID: usize> Ord for ByAddress<'_, T, ID> {
+    fn cmp(&self, other: &Self) -> std::cmp::Ordering {
+        self.0.as_ptr().cmp(&other.0.as_ptr())
+    }
+}
+
+impl<'a, T, const ID: usize> std::ops::Deref for ByAddress<'a, T, ID> {
+    type Target = Cell<T, ID>;
+
+    fn deref(&self) -> &Cell<T, ID> {
+        self.0
+    }
+}
+
+/// Returns a closure borrowing cells with `token`, for point-free iterator pipelines.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, reader, writer, Cell};
+/// let (mut token, _) = first().unwrap().token();
+/// let cells = [Cell::new(1), Cell::new(2), Cell::new(3)];
+///
+/// cells.iter().for_each(writer(&mut token, |x| *x *= 10));
+/// let total: i32 = cells.iter().map(reader(&token)).sum();
+/// assert_eq!(total, 60);
+/// ```
+pub fn reader<'a, T, U, const ID: usize>(token: &'a TokenWith<U, ID>) -> impl Fn(&'a Cell<T, ID>) -> &'a T + Copy {
+    move |cell| cell.borrow(token)
+}
+
+/// Returns a closure running `f` on each cell it's given, mutably borrowed with `token`. Unlike
+/// [reader], the closure can't hand out the borrow itself, since calling it twice with the same
+/// cell would then create two `&mut T`s.
+pub fn writer<'a, T, U, R, const ID: usize>(token: &'a mut TokenWith<U, ID>, mut f: impl FnMut(&mut T) -> R + 'a)
+    -> impl FnMut(&Cell<T, ID>) -> R + 'a
+{
+    move |cell| f(cell.borrow_mut(token))
+}
+
+/// Generates a cell holding an arbitrary `T`.
+#[cfg(feature = "arbitrary")]
+impl<'a, T: arbitrary::Arbitrary<'a>, const ID: usize> arbitrary::Arbitrary<'a> for Cell<T, ID> {
+    fn arbitrary(u: &mut arbitrary::Unstructured<'a>) -> arbitrary::Result<Self> {
+        T::arbitrary(u).map(Self::new)
+    }
+
+    fn size_hint(depth: usize) -> (usize, Option<usize>) {
+        T::size_hint(depth)
+    }
+}
+
+/// Generates a cell holding a value from `T`'s strategy. For a custom strategy, use
+/// `strategy.prop_map(Cell::new)`.
+#[cfg(feature = "proptest")]
+impl<T: proptest::arbitrary::Arbitrary + 'static, const ID: usize> proptest::arbitrary::Arbitrary for Cell<T, ID> {
+    type Parameters = T::Parameters;
+    type Strategy = proptest::strategy::Map<T::Strategy, fn(T) -> Self>;
+
+    fn arbitrary_with(args: Self::Parameters) -> Self::Strategy {
+        use proptest::strategy::Strategy;
+        T::arbitrary_with(args).prop_map(Self::new)
+    }
+}
+
+#[cfg(feature = "zerocopy")]
+#[test]
+fn zerocopy_overlay_test() {
+    use zerocopy::{FromBytes, IntoBytes};
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let mut packet = [1u8, 2, 3, 4];
+
+    let header = Cell::<[u8; 2], 1000>::mut_from_prefix(&mut packet).unwrap().0;
+    header.borrow_mut(&mut token)[0] = 9;
+    assert_eq!(header.as_mut_bytes(), [9, 2]);
+
+    assert_eq!(packet, [9, 2, 3, 4]);
+}
+
+#[test]
+fn scoped_replace_panic_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let cell = Cell::new(String::from("outer"));
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        cell.scoped_replace(&mut token, String::from("inner"), |_, _| panic!("inside scope"));
+    }));
+
+    assert!(panicked.is_err());
+    assert_eq!(cell.borrow(&token), "outer");
 }
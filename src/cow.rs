@@ -0,0 +1,87 @@
+use std::sync::Arc;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// A cell holding an `Arc<T>`: readers can take a [snapshot](Self::snapshot) that stays valid
+/// without holding on to the token, and writing through [Self::make_mut] only clones the value
+/// if a snapshot is still alive. Suited to read-mostly, configuration-style data that is read from
+/// many places (or threads) and occasionally updated.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, cow::CowCell};
+/// #[derive(Clone)]
+/// struct Config { retries: u32 }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let config = CowCell::new(Config { retries: 3 });
+///
+/// let snapshot = config.snapshot(&token);
+/// std::thread::spawn(move || assert_eq!(snapshot.retries, 3));
+///
+/// config.make_mut(&mut token).retries = 5;
+/// assert_eq!(config.get(&token).retries, 5);
+/// ```
+pub struct CowCell<T, const ID: usize> {
+    inner: Cell<Arc<T>, ID>,
+}
+
+impl<T: Default, const ID: usize> Default for CowCell<T, ID> {
+    fn default() -> Self {
+        Self::new(T::default())
+    }
+}
+
+impl<T, const ID: usize> CowCell<T, ID> {
+    pub fn new(value: T) -> Self {
+        Self::from_arc(Arc::new(value))
+    }
+
+    pub fn from_arc(value: Arc<T>) -> Self {
+        Self {
+            inner: Cell::new(value),
+        }
+    }
+
+    pub fn into_arc(self) -> Arc<T> {
+        self.inner.into_inner()
+    }
+
+    pub fn get<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.inner.borrow(token)
+    }
+
+    /// A shared handle to the current value, which later writes won't affect.
+    pub fn snapshot<U>(&self, token: &TokenWith<U, ID>) -> Arc<T> {
+        Arc::clone(self.inner.borrow(token))
+    }
+
+    /// Mutable access to the value, cloning it first if any snapshots of it are still alive.
+    #[allow(clippy::mut_from_ref)]
+    pub fn make_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut T where T: Clone {
+        Arc::make_mut(self.inner.borrow_mut(token))
+    }
+
+    /// Replaces the value without cloning it, returning the previous one.
+    pub fn replace<U>(&self, token: &mut TokenWith<U, ID>, value: T) -> Arc<T> {
+        std::mem::replace(self.inner.borrow_mut(token), Arc::new(value))
+    }
+}
+
+#[test]
+fn cow_clone_on_write_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let cell = CowCell::new(vec![1]);
+
+    let before = Arc::as_ptr(&cell.snapshot(&token));
+    cell.make_mut(&mut token).push(2);
+    assert_eq!(Arc::as_ptr(&cell.snapshot(&token)), before);
+
+    let snapshot = cell.snapshot(&token);
+    cell.make_mut(&mut token).push(3);
+    assert_eq!(*snapshot, [1, 2]);
+    assert_eq!(cell.get(&token), &[1, 2, 3]);
+}
@@ -0,0 +1,69 @@
+//! Change detection for syncing token-owned state to a UI or over the network, where only what
+//! changed since the last sync should be sent.
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+impl<T: PartialEq, const ID: usize> Cell<T, ID> {
+    /// Whether the value differs from `previous`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let title = Cell::new(String::from("Untitled"));
+    /// let synced = title.get_cloned(&token);
+    ///
+    /// assert!(!title.diff_with(&token, &synced));
+    /// title.borrow_mut(&mut token).push('*');
+    /// assert!(title.diff_with(&token, &synced));
+    /// ```
+    pub fn diff_with<U>(&self, token: &TokenWith<U, ID>, previous: &T) -> bool {
+        self.borrow(token) != previous
+    }
+}
+
+/// Compares every cell with the value at the same position in `previous`, returning the positions
+/// that changed. `previous` is brought up to date, so the next call only reports later changes.
+///
+/// Cells past the end of `previous` count as changed. If there are fewer cells than values,
+/// `previous` is truncated.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, diff::collect_changes};
+/// let (mut token, _) = first().unwrap().token();
+/// let rows = vec![Cell::new(1), Cell::new(2), Cell::new(3)];
+/// let mut synced = Vec::new();
+///
+/// assert_eq!(collect_changes(&token, &rows, &mut synced), [0, 1, 2]);
+/// assert_eq!(collect_changes(&token, &rows, &mut synced), []);
+///
+/// rows[1].set(&mut token, 20);
+/// assert_eq!(collect_changes(&token, &rows, &mut synced), [1]);
+/// assert_eq!(synced, [1, 20, 3]);
+/// ```
+pub fn collect_changes<T, U, const ID: usize>(token: &TokenWith<U, ID>, cells: &[Cell<T, ID>], previous: &mut Vec<T>) -> Vec<usize>
+where
+    T: PartialEq + Clone,
+{
+    previous.truncate(cells.len());
+    let mut changed = Vec::new();
+
+    for (i, cell) in cells.iter().enumerate() {
+        let value = cell.borrow(token);
+        match previous.get_mut(i) {
+            Some(old) if old == value => {}
+            Some(old) => {
+                old.clone_from(value);
+                changed.push(i);
+            }
+            None => {
+                previous.push(value.clone());
+                changed.push(i);
+            }
+        }
+    }
+
+    changed
+}
@@ -0,0 +1,75 @@
+use std::marker::PhantomData;
+use std::sync::Mutex;
+
+use crate::tokens::TokenWith;
+
+/// A queue of values waiting to be dropped. Values are queued with a shared token, when running
+/// their destructors could touch cells that are still borrowed, and dropped later in one go with
+/// the mutable token, when nothing in the family is borrowed.
+///
+/// Values still queued when the queue itself is dropped are dropped with it.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, drop_queue::DropQueue};
+/// let (mut token, _) = first().unwrap().token();
+/// let queue = DropQueue::new();
+/// let mut nodes = vec![Cell::new(String::from("a")), Cell::new(String::from("b"))];
+///
+/// // A shared-access phase: nodes can be detached, but not dropped yet
+/// let shared = &token;
+/// while let Some(node) = nodes.pop() {
+///     assert!(!node.borrow(shared).is_empty());
+///     queue.defer(shared, node);
+/// }
+///
+/// assert_eq!(queue.len(), 2);
+/// assert_eq!(queue.flush(&mut token), 2);
+/// assert!(queue.is_empty());
+/// ```
+pub struct DropQueue<const ID: usize> {
+    queued: Mutex<Vec<Box<dyn Send>>>,
+    _family: PhantomData<[(); ID]>,
+}
+
+impl<const ID: usize> Default for DropQueue<ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const ID: usize> DropQueue<ID> {
+    pub const fn new() -> Self {
+        Self {
+            queued: Mutex::new(Vec::new()),
+            _family: PhantomData,
+        }
+    }
+
+    /// The number of values waiting to be dropped.
+    pub fn len(&self) -> usize {
+        self.lock().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Queues `value` to be dropped by the next [Self::flush].
+    pub fn defer<T: Send + 'static, U>(&self, _: &TokenWith<U, ID>, value: T) {
+        self.lock().push(Box::new(value));
+    }
+
+    /// Drops every queued value, in the order they were queued, returning how many there were.
+    pub fn flush<U>(&self, _: &mut TokenWith<U, ID>) -> usize {
+        // Taken out first, so a destructor that panics doesn't poison the queue
+        let queued = std::mem::take(&mut *self.lock());
+        let len = queued.len();
+        drop(queued);
+        len
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Vec<Box<dyn Send>>> {
+        self.queued.lock().unwrap_or_else(|e| e.into_inner())
+    }
+}
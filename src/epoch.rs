@@ -0,0 +1,251 @@
+//! An arena that can be read concurrently without the token, using
+//! [crossbeam-epoch](::crossbeam_epoch) to defer freeing removed entries until no reader can
+//! still see them.
+
+use std::fmt::Debug;
+use std::hash::{Hash, Hasher};
+use std::marker::PhantomData;
+use std::ptr;
+
+use ::crossbeam_epoch::{self as epoch, Atomic, Guard, Owned, Shared};
+
+use crate::cells::Cell;
+use crate::sync::{AtomicPtr, Ordering};
+use crate::tokens::TokenWith;
+
+// Slots live in chunks of doubling size that never move once allocated, so readers can keep
+// using them while the writer appends
+const FIRST_CHUNK: usize = 16;
+const CHUNKS: usize = usize::BITS as usize - 4;
+
+/// A copyable handle to an entry in an [EpochArena]. Like a [WeakIndex](crate::arena::WeakIndex),
+/// it stops resolving once its entry is removed, even if the slot is reused.
+pub struct EpochIndex<T, const ID: usize> {
+    slot: usize,
+    generation: u32,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T, const ID: usize> Clone for EpochIndex<T, ID> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, const ID: usize> Copy for EpochIndex<T, ID> {}
+
+impl<T, const ID: usize> PartialEq for EpochIndex<T, ID> {
+    fn eq(&self, other: &Self) -> bool {
+        self.slot == other.slot && self.generation == other.generation
+    }
+}
+
+impl<T, const ID: usize> Eq for EpochIndex<T, ID> {}
+
+impl<T, const ID: usize> Hash for EpochIndex<T, ID> {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.slot.hash(state);
+        self.generation.hash(state);
+    }
+}
+
+impl<T, const ID: usize> Debug for EpochIndex<T, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("EpochIndex")
+            .field("slot", &self.slot)
+            .field("generation", &self.generation)
+            .finish()
+    }
+}
+
+struct Entry<T> {
+    generation: u32,
+    value: T,
+}
+
+// Only touched by the writer, through the token
+struct Writer {
+    free: Vec<usize>,
+    generations: Vec<u32>,
+}
+
+/// An arena for read-heavy data like routing tables and caches: any number of threads can
+/// [pin](Self::pin) an epoch and read entries without the token, while a single writer holding
+/// the family's `&mut Token` inserts, replaces and removes them. Removed or replaced values are
+/// only dropped once every reader that could still see them has unpinned.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, epoch::EpochArena};
+/// let (mut token, _) = first().unwrap().token();
+/// let routes = EpochArena::new();
+/// let route = routes.insert(&mut token, "10.0.0.1");
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         let guard = routes.pin();
+///         let hop = routes.get(route, &guard);
+///         assert!(matches!(hop, Some(&"10.0.0.1" | &"10.0.0.2") | None));
+///     });
+///
+///     routes.replace(&mut token, route, "10.0.0.2").unwrap();
+/// });
+///
+/// assert_eq!(routes.get(route, &routes.pin()), Some(&"10.0.0.2"));
+/// assert_eq!(routes.remove(&mut token, route), true);
+/// assert_eq!(routes.get(route, &routes.pin()), None);
+/// ```
+///
+/// Values are `Send + 'static` because crossbeam's collector drops removed and replaced values
+/// whenever it gets around to it: on any thread, possibly after everything borrowed by the arena's
+/// owner has gone away.
+pub struct EpochArena<T: Send + 'static, const ID: usize> {
+    chunks: [AtomicPtr<Atomic<Entry<T>>>; CHUNKS],
+    writer: Cell<Writer, ID>,
+}
+
+// Safety: readers on any thread get `&T`s, and removed values may be dropped on any thread,
+// which the struct's `T: Send` bound covers
+unsafe impl<T: Send + 'static, const ID: usize> Send for EpochArena<T, ID> {}
+unsafe impl<T: Send + Sync + 'static, const ID: usize> Sync for EpochArena<T, ID> {}
+
+impl<T: Send + 'static, const ID: usize> Default for EpochArena<T, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn locate(slot: usize) -> (usize, usize) {
+    let chunk = (slot / FIRST_CHUNK + 1).ilog2() as usize;
+    (chunk, slot - FIRST_CHUNK * ((1 << chunk) - 1))
+}
+
+impl<T: Send + 'static, const ID: usize> EpochArena<T, ID> {
+    pub fn new() -> Self {
+        Self {
+            chunks: std::array::from_fn(|_| AtomicPtr::new(ptr::null_mut())),
+            writer: Cell::new(Writer { free: Vec::new(), generations: Vec::new() }),
+        }
+    }
+
+    /// The number of live entries.
+    pub fn len<U>(&self, token: &TokenWith<U, ID>) -> usize {
+        let writer = self.writer.borrow(token);
+        writer.generations.len() - writer.free.len()
+    }
+
+    pub fn is_empty<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.len(token) == 0
+    }
+
+    fn slot(&self, slot: usize) -> Option<&Atomic<Entry<T>>> {
+        let (chunk, offset) = locate(slot);
+        let chunk = self.chunks[chunk].load(Ordering::Acquire);
+        (!chunk.is_null()).then(|| unsafe {&*chunk.add(offset)})
+    }
+
+    /// Pins the current thread's epoch. Entries read through the guard stay valid until it is
+    /// dropped.
+    pub fn pin(&self) -> Guard {
+        epoch::pin()
+    }
+
+    pub fn get<'g>(&'g self, index: EpochIndex<T, ID>, guard: &'g Guard) -> Option<&'g T> {
+        let entry = self.slot(index.slot)?.load(Ordering::Acquire, guard);
+        unsafe {entry.as_ref()}
+            .filter(|entry| entry.generation == index.generation)
+            .map(|entry| &entry.value)
+    }
+
+    pub fn insert<U>(&self, token: &mut TokenWith<U, ID>, value: T) -> EpochIndex<T, ID> {
+        let writer = self.writer.borrow_mut(token);
+        let slot = writer.free.pop().unwrap_or_else(|| {
+            writer.generations.push(0);
+            writer.generations.len() - 1
+        });
+        let generation = writer.generations[slot];
+
+        let (chunk, _) = locate(slot);
+        if self.chunks[chunk].load(Ordering::Relaxed).is_null() {
+            let slots: Box<[Atomic<Entry<T>>]> = (0..FIRST_CHUNK << chunk).map(|_| Atomic::null()).collect();
+            self.chunks[chunk].store(Box::into_raw(slots) as *mut _, Ordering::Release);
+        }
+
+        self.slot(slot).unwrap().store(Owned::new(Entry { generation, value }), Ordering::Release);
+        EpochIndex { slot, generation, _type: PhantomData }
+    }
+
+    fn swap<U>(&self, _: &mut TokenWith<U, ID>, slot: usize, new: Shared<'_, Entry<T>>) {
+        let guard = epoch::pin();
+        let old = self.slot(slot).unwrap().swap(new, Ordering::AcqRel, &guard);
+
+        // Safety: the entry is no longer reachable, and readers that already loaded it are pinned
+        unsafe {guard.defer_destroy(old)};
+    }
+
+    /// Replaces the value `index` points to, or hands `value` back if the entry has been removed.
+    pub fn replace<U>(&self, token: &mut TokenWith<U, ID>, index: EpochIndex<T, ID>, value: T) -> Result<(), T> {
+        if self.writer.borrow(token).generations.get(index.slot) != Some(&index.generation) {
+            return Err(value);
+        }
+
+        let entry = Owned::new(Entry { generation: index.generation, value });
+        self.swap(token, index.slot, entry.into_shared(unsafe {epoch::unprotected()}));
+        Ok(())
+    }
+
+    /// Removes the entry `index` points to. Returns false if it had already been removed.
+    pub fn remove<U>(&self, token: &mut TokenWith<U, ID>, index: EpochIndex<T, ID>) -> bool {
+        let writer = self.writer.borrow_mut(token);
+        match writer.generations.get_mut(index.slot) {
+            Some(generation) if *generation == index.generation => {
+                *generation = generation.wrapping_add(1);
+                writer.free.push(index.slot);
+            }
+            _ => return false,
+        }
+
+        self.swap(token, index.slot, Shared::null());
+        true
+    }
+}
+
+impl<T: Send + 'static, const ID: usize> Drop for EpochArena<T, ID> {
+    fn drop(&mut self) {
+        for (chunk, slots) in self.chunks.iter().enumerate() {
+            let slots = slots.load(Ordering::Relaxed);
+            if slots.is_null() {
+                break;
+            }
+
+            // Safety: `&mut self` means there are no readers left
+            let slots = unsafe {Box::from_raw(ptr::slice_from_raw_parts_mut(slots, FIRST_CHUNK << chunk))};
+            for slot in slots.iter() {
+                let entry = slot.load(Ordering::Relaxed, unsafe {epoch::unprotected()});
+                if !entry.is_null() {
+                    drop(unsafe {entry.into_owned()});
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn epoch_arena_reuse_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let arena = EpochArena::new();
+
+    let indices: Vec<_> = (0..100).map(|i| arena.insert(&mut token, i)).collect();
+    assert!(arena.remove(&mut token, indices[5]));
+    assert!(!arena.remove(&mut token, indices[5]));
+
+    // Reuses slot 5 with a new generation
+    let reused = arena.insert(&mut token, 500);
+    let guard = arena.pin();
+    assert_eq!(arena.get(indices[5], &guard), None);
+    assert_eq!(arena.get(reused, &guard), Some(&500));
+    assert_eq!(arena.get(indices[99], &guard), Some(&99));
+    assert_eq!(arena.len(&token), 100);
+}
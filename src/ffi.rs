@@ -0,0 +1,244 @@
+//! A C API for token-gated byte buffers.
+//!
+//! C can't express const generics, so families are checked at runtime instead: every [FcToken]
+//! carries a family number, every [FcCell] remembers the family it belongs to, and borrowing
+//! fails (returns null) if the two don't match or if the borrow would alias a mutable one. The
+//! Rust side can move its own tokens and cells across with [FcToken::from_token] and
+//! [FcCell::from_cell], and get them back with [FcToken::into_token] and [FcCell::into_cell].
+//!
+//! Every function is `unsafe`: pointers must either be null (where documented) or have been
+//! returned by this module and not yet freed. Borrows are counted atomically, so tokens and cells
+//! can be used from several threads at once, but a cell must not be freed while another thread
+//! may still use it. The declarations for C are in `include/frankencell.h`.
+
+use std::ptr;
+
+use crate::cells::Cell;
+use crate::sync::{statics, AtomicUsize, Ordering, UnsafeCell};
+use crate::tokens::Token;
+
+// Families created from C count down from the top so they can't collide with const IDs
+static NEXT_FAMILY: statics::AtomicUsize = statics::AtomicUsize::new(usize::MAX);
+
+// `FcCell::state` while the cell is mutably borrowed
+const WRITER: usize = usize::MAX;
+
+/// An opaque handle proving ownership of a family.
+pub struct FcToken {
+    family: usize,
+}
+
+/// An opaque, token-gated byte buffer.
+pub struct FcCell {
+    family: usize,
+    data: UnsafeCell<Vec<u8>>,
+    // The number of shared borrows, or `WRITER`
+    state: AtomicUsize,
+}
+
+// Safety: `data` is only accessed through borrows that `state` has granted
+unsafe impl Sync for FcCell {}
+
+impl FcToken {
+    /// Hands a family's token over to C.
+    pub fn from_token<const ID: usize>(_: Token<ID>) -> Box<Self> {
+        Box::new(Self { family: ID })
+    }
+
+    /// Takes a family's token back from C, if `self` belongs to family `ID`.
+    pub fn into_token<const ID: usize>(self: Box<Self>) -> Result<Token<ID>, Box<Self>> {
+        if self.family == ID {
+            Ok(unsafe {Token::new(())})
+        } else {
+            Err(self)
+        }
+    }
+}
+
+impl FcCell {
+    pub fn from_cell<const ID: usize>(cell: Cell<Vec<u8>, ID>) -> Box<Self> {
+        Box::new(Self {
+            family: ID,
+            data: UnsafeCell::new(cell.into_inner()),
+            state: AtomicUsize::new(0),
+        })
+    }
+
+    /// Takes a cell back from C, if it belongs to family `ID` and isn't currently borrowed.
+    pub fn into_cell<const ID: usize>(self: Box<Self>) -> Result<Cell<Vec<u8>, ID>, Box<Self>> {
+        if self.family == ID && self.state.load(Ordering::Acquire) == 0 {
+            Ok(Cell::new(self.data.with_mut(|data| unsafe {std::mem::take(&mut *data)})))
+        } else {
+            Err(self)
+        }
+    }
+}
+
+/// Creates a token for a new family. Returns null if every family number has been used.
+///
+/// # Safety
+/// Families are numbered down from `usize::MAX`, and no other token of a family created here may
+/// ever exist: no token with the same ID may be created on the Rust side, whether through
+/// [first()](crate::first) and its builders or otherwise. The result must be freed with
+/// [fc_token_free].
+#[no_mangle]
+pub unsafe extern "C" fn fc_token_new() -> *mut FcToken {
+    let family = NEXT_FAMILY.fetch_sub(1, Ordering::Relaxed);
+    if family < usize::MAX / 2 {
+        return ptr::null_mut();
+    }
+
+    Box::into_raw(Box::new(FcToken { family }))
+}
+
+/// # Safety
+/// `token` must be null or come from [fc_token_new].
+#[no_mangle]
+pub unsafe extern "C" fn fc_token_free(token: *mut FcToken) {
+    if !token.is_null() {
+        drop(unsafe {Box::from_raw(token)});
+    }
+}
+
+/// Creates a cell in `token`'s family holding a copy of `len` bytes from `data`. Returns null if
+/// `token` is null.
+///
+/// # Safety
+/// `data` must be valid for reads of `len` bytes, or null if `len` is 0.
+#[no_mangle]
+pub unsafe extern "C" fn fc_cell_new(token: *const FcToken, data: *const u8, len: usize) -> *mut FcCell {
+    let Some(token) = (unsafe {token.as_ref()}) else {
+        return ptr::null_mut();
+    };
+
+    let data = if len == 0 {
+        Vec::new()
+    } else {
+        unsafe {std::slice::from_raw_parts(data, len)}.to_vec()
+    };
+
+    Box::into_raw(Box::new(FcCell {
+        family: token.family,
+        data: UnsafeCell::new(data),
+        state: AtomicUsize::new(0),
+    }))
+}
+
+/// Frees a cell. Returns false (and does nothing) if the cell is still borrowed.
+///
+/// # Safety
+/// `cell` must be null or come from this module, and no other thread may use it concurrently.
+#[no_mangle]
+pub unsafe extern "C" fn fc_cell_free(cell: *mut FcCell) -> bool {
+    match unsafe {cell.as_ref()} {
+        None => true,
+        Some(c) if c.state.load(Ordering::Acquire) != 0 => false,
+        Some(_) => {
+            drop(unsafe {Box::from_raw(cell)});
+            true
+        }
+    }
+}
+
+unsafe fn check<'a>(cell: *mut FcCell, token: *const FcToken) -> Option<&'a FcCell> {
+    let cell = unsafe {cell.as_ref()}?;
+    let token = unsafe {token.as_ref()}?;
+
+    (cell.family == token.family).then_some(cell)
+}
+
+/// Borrows the cell's contents, writing their length to `len`. Returns null if `token` belongs to
+/// a different family or the cell is mutably borrowed. Must be paired with
+/// [fc_cell_release].
+///
+/// # Safety
+/// `len` must be valid for writes. The returned pointer is only valid until the matching
+/// [fc_cell_release].
+#[no_mangle]
+pub unsafe extern "C" fn fc_cell_borrow(cell: *mut FcCell, token: *const FcToken, len: *mut usize) -> *const u8 {
+    let Some(cell) = (unsafe {check(cell, token)}) else {
+        return ptr::null();
+    };
+
+    let shared = cell.state.fetch_update(Ordering::Acquire, Ordering::Relaxed, |readers| {
+        // Also refuses a count that would reach `WRITER`
+        (readers < WRITER - 1).then_some(readers + 1)
+    });
+    if shared.is_err() {
+        return ptr::null();
+    }
+
+    cell.data.with_mut(|data| unsafe {
+        *len = (*data).len();
+        (*data).as_ptr()
+    })
+}
+
+/// Mutably borrows the cell's contents, writing their length to `len`. Returns null if `token`
+/// belongs to a different family or the cell is already borrowed. Must be paired with
+/// [fc_cell_release].
+///
+/// # Safety
+/// `len` must be valid for writes. The returned pointer is only valid until the matching
+/// [fc_cell_release].
+#[no_mangle]
+pub unsafe extern "C" fn fc_cell_borrow_mut(cell: *mut FcCell, token: *mut FcToken, len: *mut usize) -> *mut u8 {
+    let Some(cell) = (unsafe {check(cell, token)}) else {
+        return ptr::null_mut();
+    };
+
+    if cell.state.compare_exchange(0, WRITER, Ordering::Acquire, Ordering::Relaxed).is_err() {
+        return ptr::null_mut();
+    }
+
+    cell.data.with_mut(|data| unsafe {
+        *len = (*data).len();
+        (*data).as_mut_ptr()
+    })
+}
+
+/// Ends the mutable borrow, or one of the shared borrows, of a cell.
+///
+/// # Safety
+/// `cell` must be null or come from this module.
+#[no_mangle]
+pub unsafe extern "C" fn fc_cell_release(cell: *mut FcCell) {
+    if let Some(cell) = unsafe {cell.as_ref()} {
+        let _ = cell.state.fetch_update(Ordering::Release, Ordering::Relaxed, |state| match state {
+            WRITER => Some(0),
+            readers => readers.checked_sub(1),
+        });
+    }
+}
+
+#[test]
+fn ffi_borrow_test() {
+    use crate::TokenBuilder;
+
+    unsafe {
+        let (token, _) = TokenBuilder::<1000>::new().token();
+        let rust_token = Box::into_raw(FcToken::from_token(token));
+        let c_token = fc_token_new();
+
+        let cell = fc_cell_new(rust_token, b"abc".as_ptr(), 3);
+        let mut len = 0;
+
+        assert!(fc_cell_borrow(cell, c_token, &mut len).is_null());
+
+        let data = fc_cell_borrow(cell, rust_token, &mut len);
+        assert_eq!(std::slice::from_raw_parts(data, len), b"abc");
+        assert!(fc_cell_borrow_mut(cell, rust_token, &mut len).is_null());
+        assert!(!fc_cell_free(cell));
+        fc_cell_release(cell);
+
+        let data = fc_cell_borrow_mut(cell, rust_token, &mut len);
+        *data = b'x';
+        fc_cell_release(cell);
+
+        let cell = Box::from_raw(cell).into_cell::<1000>().ok().unwrap();
+        let token = Box::from_raw(rust_token).into_token::<1000>().ok().unwrap();
+        assert_eq!(cell.borrow(&token), b"xbc");
+
+        fc_token_free(c_token);
+    }
+}
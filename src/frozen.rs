@@ -0,0 +1,124 @@
+use std::fmt::Debug;
+use std::ops::Deref;
+
+use crate::cells::Cell;
+use crate::tokens::Token;
+
+/// A read-only view of a value that used to live in a [Cell], created with [Cell::freeze]. Since
+/// it can never be mutated again, reading it no longer requires a token.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell};
+/// let (mut token, _) = first().unwrap().token();
+///
+/// let config = Cell::new(vec!["a"]);
+/// config.borrow_mut(&mut token).push("b");
+///
+/// let config = config.freeze();
+/// std::thread::scope(|s| {
+///     s.spawn(|| assert_eq!(config.get().len(), 2));
+///     s.spawn(|| assert_eq!(config[0], "a"));
+/// });
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Frozen<T, const ID: usize> {
+    inner: T,
+}
+
+impl<T, const ID: usize> Frozen<T, ID> {
+    pub const fn get(&self) -> &T {
+        &self.inner
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner
+    }
+
+    /// Turns this back into a mutable [Cell]. This requires owning the `Frozen`, so no other
+    /// references to it can exist.
+    pub fn thaw(self) -> Cell<T, ID> {
+        Cell::new(self.inner)
+    }
+}
+
+impl<T, const ID: usize> Deref for Frozen<T, ID> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner
+    }
+}
+
+impl<T: Debug, const ID: usize> Debug for Frozen<T, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        self.inner.fmt(f)
+    }
+}
+
+impl<T, const ID: usize> Cell<T, ID> {
+    /// Converts this cell into a [Frozen] that can be read without a token. Since this consumes
+    /// the cell, no outstanding borrows can exist.
+    pub fn freeze(self) -> Frozen<T, ID> {
+        Frozen {
+            inner: self.into_inner(),
+        }
+    }
+}
+
+/// A shared proof that family `ID` will never be mutated again, created with
+/// [Token::into_frozen]. It's `Copy`, so it can be handed to every thread and stored anywhere,
+/// and it dereferences to the family's token, so [Cell::borrow] accepts it as it is.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell};
+/// let (mut token, _) = first().unwrap().token();
+/// let assets: &'static [Cell<&str, 0>] = Box::leak(Box::new([Cell::new("grass.png"), Cell::new("")]));
+/// *assets[1].borrow_mut(&mut token) = "stone.png";
+///
+/// let frozen = token.into_frozen();
+/// std::thread::scope(|s| {
+///     for asset in assets {
+///         s.spawn(move || assert!(asset.borrow(&frozen).ends_with(".png")));
+///     }
+/// });
+/// ```
+///
+/// The family's token is gone for good:
+/// ```compile_fail
+/// # use frankencell::{first, Cell};
+/// let (mut token, _) = first().unwrap().token();
+/// let cell = Cell::new(0);
+///
+/// let frozen = token.into_frozen();
+/// cell.borrow_mut(&mut token);
+/// ```
+#[derive(Clone, Copy)]
+pub struct FrozenToken<const ID: usize> {
+    token: &'static Token<ID>,
+}
+
+impl<const ID: usize> Token<ID> {
+    /// Gives up this token for a [FrozenToken], so the family's cells can only ever be read from
+    /// then on.
+    pub fn into_frozen(self) -> FrozenToken<ID> {
+        // A `Token` is zero-sized, so this doesn't allocate
+        FrozenToken { token: Box::leak(Box::new(self)) }
+    }
+}
+
+impl<const ID: usize> Deref for FrozenToken<ID> {
+    type Target = Token<ID>;
+
+    fn deref(&self) -> &Token<ID> {
+        self.token
+    }
+}
+
+impl<const ID: usize> Debug for FrozenToken<ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "FrozenToken<{}>", ID)
+    }
+}
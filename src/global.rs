@@ -0,0 +1,291 @@
+use std::ops::{Deref, DerefMut};
+use std::sync::{Mutex, MutexGuard};
+
+use crate::cells::Cell;
+use crate::sync::{statics::AtomicUsize, Ordering};
+use crate::tokens::{Token, TokenWith};
+
+enum State<const ID: usize> {
+    Unclaimed,
+    Held(Token<ID>),
+    Taken,
+}
+
+/// A `static`-friendly cell that owns its family's token, so application-wide state can be
+/// accessed from anywhere with [Self::with]/[Self::with_mut] instead of plumbing a token down from
+/// `main`. The token is only created on first access, and can be taken out with
+/// [Self::take_token] by code that wants to use it directly.
+///
+/// Usually declared with [static_cell!](crate::static_cell).
+///
+/// Only the token is claimed lazily, not the family: family IDs are const generics, so one can't
+/// be handed out at runtime, and the ID is instead picked by hand with an `unsafe` promise that
+/// nothing else uses it. This is a deliberate departure from claiming a family on first access.
+pub struct GlobalCell<T, const ID: usize> {
+    cell: Cell<T, ID>,
+    state: Mutex<State<ID>>,
+    // Identifies the thread holding `state`, so re-entrant calls panic instead of deadlocking
+    holder: AtomicUsize,
+}
+
+// Keeps `GlobalCell::holder` up to date for as long as `state` is locked
+struct Locked<'a, const ID: usize> {
+    state: MutexGuard<'a, State<ID>>,
+    holder: &'a AtomicUsize,
+}
+
+impl<const ID: usize> Deref for Locked<'_, ID> {
+    type Target = State<ID>;
+
+    fn deref(&self) -> &State<ID> {
+        &self.state
+    }
+}
+
+impl<const ID: usize> DerefMut for Locked<'_, ID> {
+    fn deref_mut(&mut self) -> &mut State<ID> {
+        &mut self.state
+    }
+}
+
+impl<const ID: usize> Drop for Locked<'_, ID> {
+    fn drop(&mut self) {
+        // Runs before `state` is unlocked, so it can't clear another thread's entry
+        self.holder.store(0, Ordering::Relaxed);
+    }
+}
+
+// A non-zero number unique to each live thread
+fn thread_key() -> usize {
+    thread_local! {
+        static KEY: u8 = const { 0 };
+    }
+    KEY.with(|key| key as *const u8 as usize)
+}
+
+impl<T, const ID: usize> GlobalCell<T, ID> {
+    /// # Safety
+    /// The `GlobalCell` lazily creates a `Token<ID>`, so no other token with this ID may ever
+    /// exist; in particular, `ID` must never be reached by a [TokenBuilder](crate::TokenBuilder)
+    /// chain starting from [first()](crate::first).
+    pub const unsafe fn new(value: T) -> Self {
+        Self {
+            cell: Cell::new(value),
+            state: Mutex::new(State::Unclaimed),
+            holder: AtomicUsize::new(0),
+        }
+    }
+
+    #[track_caller]
+    fn lock(&self) -> Locked<'_, ID> {
+        // Only this thread ever stores its own key, so a stale value can't cause a false positive
+        let key = thread_key();
+        assert!(self.holder.load(Ordering::Relaxed) != key, "GlobalCell used from inside its own `with` or `with_mut`");
+
+        let mut state = self.state.lock().unwrap_or_else(|e| e.into_inner());
+        self.holder.store(key, Ordering::Relaxed);
+        if let State::Unclaimed = *state {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(family = ID, "GlobalCell claimed its token");
+
+            *state = State::Held(unsafe {Token::new(())});
+        }
+        Locked { state, holder: &self.holder }
+    }
+
+    /// Runs `f` with shared access to the value. The global stays locked while `f` runs, so other
+    /// threads using it wait for `f` to return.
+    ///
+    /// # Panics
+    /// Panics if the token has been taken out with [Self::take_token] and not restored, or if `f`
+    /// uses this `GlobalCell` again.
+    #[track_caller]
+    pub fn with<R>(&self, f: impl FnOnce(&T) -> R) -> R {
+        match &*self.lock() {
+            State::Held(token) => f(self.cell.borrow(token)),
+            _ => panic!("GlobalCell's token has been taken"),
+        }
+    }
+
+    /// Runs `f` with mutable access to the value. The global stays locked while `f` runs.
+    ///
+    /// # Panics
+    /// Panics if the token has been taken out with [Self::take_token] and not restored, or if `f`
+    /// uses this `GlobalCell` again.
+    #[track_caller]
+    pub fn with_mut<R>(&self, f: impl FnOnce(&mut T) -> R) -> R {
+        match &mut *self.lock() {
+            State::Held(token) => f(self.cell.borrow_mut(token)),
+            _ => panic!("GlobalCell's token has been taken"),
+        }
+    }
+
+    /// Takes the family's token out of the global so it can be used with [Self::cell] directly.
+    /// Returns `None` if it has already been taken.
+    ///
+    /// # Panics
+    /// Panics if called from inside [Self::with] or [Self::with_mut] on the same `GlobalCell`.
+    #[track_caller]
+    pub fn take_token(&self) -> Option<Token<ID>> {
+        match std::mem::replace(&mut *self.lock(), State::Taken) {
+            State::Held(token) => Some(token),
+            _ => None,
+        }
+    }
+
+    /// Puts a token previously returned by [Self::take_token] back.
+    #[track_caller]
+    pub fn restore_token(&self, token: Token<ID>) {
+        *self.lock() = State::Held(token);
+    }
+
+    /// The underlying cell, for use with a token from [Self::take_token].
+    pub fn cell(&self) -> &Cell<T, ID> {
+        &self.cell
+    }
+}
+
+/// A replacement for `static mut` in bare-metal code: a `static` that starts out empty, is filled
+/// in once during startup with [Self::init], and is then used with the family's token like any
+/// other [Cell]. Unlike [GlobalCell], it doesn't own a token, so it needs no `unsafe` and no
+/// locking.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, global::StaticTokenCell};
+/// struct Uart { baud: u32 }
+///
+/// static UART: StaticTokenCell<Uart, 0> = StaticTokenCell::new();
+///
+/// let (mut token, _) = first().unwrap().token();
+/// assert!(UART.get(&token).is_none());
+///
+/// UART.init(&mut token, Uart { baud: 9600 }).ok().unwrap();
+/// assert!(UART.init(&mut token, Uart { baud: 115200 }).is_err());
+///
+/// UART.borrow_mut(&mut token).baud *= 2;
+/// assert_eq!(UART.borrow(&token).baud, 19200);
+/// ```
+pub struct StaticTokenCell<T, const ID: usize> {
+    inner: Cell<Option<T>, ID>,
+}
+
+impl<T, const ID: usize> Default for StaticTokenCell<T, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const ID: usize> StaticTokenCell<T, ID> {
+    pub const fn new() -> Self {
+        Self {
+            inner: Cell::new(None),
+        }
+    }
+
+    /// Fills in the value, returning a reference to it, or hands `value` back if the cell has
+    /// already been initialized.
+    #[allow(clippy::mut_from_ref)]
+    pub fn init<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>, value: T) -> Result<&'a mut T, T> {
+        match self.inner.borrow_mut(token) {
+            Some(_) => Err(value),
+            slot => Ok(slot.insert(value)),
+        }
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn get_or_init<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>, f: impl FnOnce() -> T) -> &'a mut T {
+        self.inner.borrow_mut(token).get_or_insert_with(f)
+    }
+
+    pub fn is_initialized<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.inner.borrow(token).is_some()
+    }
+
+    pub fn get<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> Option<&'a T> {
+        self.inner.borrow(token).as_ref()
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn get_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> Option<&'a mut T> {
+        self.inner.borrow_mut(token).as_mut()
+    }
+
+    /// # Panics
+    /// Panics if the cell hasn't been initialized.
+    pub fn borrow<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.get(token).expect("StaticTokenCell used before being initialized")
+    }
+
+    /// # Panics
+    /// Panics if the cell hasn't been initialized.
+    #[allow(clippy::mut_from_ref)]
+    pub fn borrow_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        self.get_mut(token).expect("StaticTokenCell used before being initialized")
+    }
+
+    /// Takes the value out, leaving the cell uninitialized again.
+    pub fn take<U>(&self, token: &mut TokenWith<U, ID>) -> Option<T> {
+        self.inner.borrow_mut(token).take()
+    }
+}
+
+/// Declares a `static` [GlobalCell](crate::global::GlobalCell) in the given family.
+///
+/// The `unsafe` is required since the declaration carries [GlobalCell::new]'s safety contract:
+/// nothing else may ever create a token with the given family ID.
+///
+/// # Example
+/// ```rust
+/// use frankencell::static_cell;
+///
+/// static_cell! {
+///     // Safety: this program never builds more than a handful of tokens with `first()`
+///     pub unsafe static HITS: u32 = 0; family = 1_000_000;
+/// }
+///
+/// fn hit() -> u32 {
+///     HITS.with_mut(|hits| {*hits += 1; *hits})
+/// }
+///
+/// hit();
+/// assert_eq!(hit(), 2);
+/// ```
+///
+/// [GlobalCell::new]: crate::global::GlobalCell::new
+#[macro_export]
+macro_rules! static_cell {
+    ($(#[$attr:meta])* $vis:vis unsafe static $name:ident: $t:ty = $init:expr; family = $id:expr;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::global::GlobalCell<$t, {$id}> = {
+            let value: $t = $init;
+            unsafe {$crate::global::GlobalCell::new(value)}
+        };
+    }
+}
+
+#[test]
+fn global_cell_test() {
+    static_cell! {
+        unsafe static NAMES: Vec<&'static str> = Vec::new(); family = 1000;
+    }
+
+    NAMES.with_mut(|names| names.push("a"));
+
+    let mut token = NAMES.take_token().unwrap();
+    assert!(NAMES.take_token().is_none());
+    NAMES.cell().borrow_mut(&mut token).push("b");
+    NAMES.restore_token(token);
+
+    assert_eq!(NAMES.with(|names| names.len()), 2);
+}
+
+#[test]
+#[should_panic(expected = "from inside its own")]
+fn global_cell_reentry_test() {
+    static_cell! {
+        unsafe static COUNT: u32 = 0; family = 1001;
+    }
+
+    COUNT.with(|_| COUNT.with_mut(|count| *count += 1));
+}
@@ -0,0 +1,175 @@
+use std::slice::{ChunksExact, ChunksExactMut, GetDisjointMutError};
+
+use crate::cells::Cell;
+use crate::slice::CellSliceExt;
+use crate::tokens::TokenWith;
+
+/// A fixed-size, row-major 2D grid of cells. Single cells are reached by indexing with `(x, y)`,
+/// while whole rows and columns can be borrowed mutably at the same time with [Self::rows_mut],
+/// [Self::columns_mut] and [Self::split_rows_mut], since they never overlap.
+///
+/// # Example
+/// A stencil that adds each row's neighbours above into it, reading one row while writing the
+/// next:
+/// ```rust
+/// # use frankencell::{first, grid::CellGrid};
+/// let (mut token, _) = first().unwrap().token();
+/// let grid = CellGrid::from_fn(3, 3, |x, y| x + y);
+///
+/// for y in 1..grid.height() {
+///     let [above, row] = grid.split_rows_mut(&mut token, [y - 1, y]).unwrap();
+///     for (x, value) in row.iter_mut().enumerate() {
+///         *value += above[x.saturating_sub(1)..(x + 2).min(above.len())].iter().sum::<usize>();
+///     }
+/// }
+///
+/// assert_eq!(grid[(1, 1)].borrow(&token), &(2 + 0 + 1 + 2));
+/// ```
+pub struct CellGrid<T, const ID: usize> {
+    cells: Box<[Cell<T, ID>]>,
+    width: usize,
+}
+
+impl<T, const ID: usize> CellGrid<T, ID> {
+    /// Creates a grid filled with clones of `value`.
+    ///
+    /// # Panics
+    /// Panics if `width` is 0.
+    pub fn new(width: usize, height: usize, value: T) -> Self where T: Clone {
+        Self::from_fn(width, height, |_, _| value.clone())
+    }
+
+    /// Creates a grid by calling `f(x, y)` for every position, row by row.
+    ///
+    /// # Panics
+    /// Panics if `width` is 0.
+    pub fn from_fn(width: usize, height: usize, mut f: impl FnMut(usize, usize) -> T) -> Self {
+        assert!(width > 0, "CellGrid must have a non-zero width");
+
+        let cells = (0..height)
+            .flat_map(|y| (0..width).map(move |x| (x, y)))
+            .map(|(x, y)| Cell::new(f(x, y)))
+            .collect();
+
+        Self { cells, width }
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.cells.len() / self.width
+    }
+
+    /// The cell at `(x, y)`, or `None` if it is out of bounds.
+    pub fn get(&self, x: usize, y: usize) -> Option<&Cell<T, ID>> {
+        if x < self.width {
+            self.cells.get(y * self.width + x)
+        } else {
+            None
+        }
+    }
+
+    /// All of the grid's cells, row by row.
+    pub fn as_slice(&self) -> &[Cell<T, ID>] {
+        &self.cells
+    }
+
+    pub fn rows(&self) -> ChunksExact<'_, Cell<T, ID>> {
+        self.cells.chunks_exact(self.width)
+    }
+
+    /// Mutably borrows every row at once.
+    #[allow(clippy::mut_from_ref)]
+    pub fn rows_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> ChunksExactMut<'a, T> {
+        self.cells.as_inner_slice_mut(token).chunks_exact_mut(self.width)
+    }
+
+    /// Mutably borrows every column at once. Each column yields its values from top to bottom.
+    #[allow(clippy::mut_from_ref)]
+    pub fn columns_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> impl Iterator<Item = impl Iterator<Item = &'a mut T>> {
+        let _ = token;
+        let width = self.width;
+
+        (0..width).map(move |x| {
+            // Safety: columns never share a cell, and the token is mutably borrowed for `'a`
+            self.cells.get(x..).unwrap_or_default().iter().step_by(width).map(|cell| unsafe {&mut *cell.inner.get()})
+        })
+    }
+
+    /// Mutably borrows the rows numbered in `rows`, failing if any of them are out of bounds or
+    /// the same row is asked for twice.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, grid::CellGrid};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let grid = CellGrid::new(4, 2, 0);
+    ///
+    /// assert!(grid.split_rows_mut(&mut token, [0, 0]).is_err());
+    /// assert!(grid.split_rows_mut(&mut token, [0, 2]).is_err());
+    ///
+    /// let [a, b] = grid.split_rows_mut(&mut token, [1, 0]).unwrap();
+    /// a.swap_with_slice(b);
+    /// ```
+    #[allow(clippy::mut_from_ref)]
+    pub fn split_rows_mut<'a, U, const N: usize>(
+        &'a self,
+        token: &'a mut TokenWith<U, ID>,
+        rows: [usize; N],
+    ) -> Result<[&'a mut [T]; N], GetDisjointMutError> {
+        let width = self.width;
+        let ranges = rows.map(|y| {
+            let start = y.checked_mul(width)?;
+            Some(start..start.checked_add(width)?)
+        });
+        if ranges.iter().any(Option::is_none) {
+            return Err(GetDisjointMutError::IndexOutOfBounds);
+        }
+
+        self.cells.as_inner_slice_mut(token).get_disjoint_mut(ranges.map(Option::unwrap))
+    }
+
+    pub fn into_vec(self) -> Vec<T> {
+        self.cells.into_vec().into_iter().map(Cell::into_inner).collect()
+    }
+}
+
+impl<T, const ID: usize> std::ops::Index<(usize, usize)> for CellGrid<T, ID> {
+    type Output = Cell<T, ID>;
+
+    /// # Panics
+    /// Panics if `(x, y)` is out of bounds.
+    fn index(&self, (x, y): (usize, usize)) -> &Cell<T, ID> {
+        self.get(x, y).expect("CellGrid position out of bounds")
+    }
+}
+
+#[test]
+fn grid_columns_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let grid = CellGrid::from_fn(3, 2, |x, y| x * 10 + y);
+
+    let mut columns: Vec<_> = grid.columns_mut(&mut token).collect();
+    let (left, right) = columns.split_at_mut(1);
+    for (a, b) in left[0].by_ref().zip(right[1].by_ref()) {
+        std::mem::swap(a, b);
+    }
+    drop(columns);
+
+    assert_eq!(grid.into_vec(), [20, 10, 0, 21, 11, 1]);
+}
+
+#[test]
+fn grid_empty_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let grid = CellGrid::<u8, 1000>::new(usize::MAX, 0, 0);
+
+    assert!(grid.columns_mut(&mut token).take(3).all(|mut column| column.next().is_none()));
+    assert!(grid.split_rows_mut(&mut token, [2]).is_err());
+}
@@ -0,0 +1,107 @@
+//! Passing a family's token, optionally bundled with the cells it governs, to another thread over
+//! a [crossbeam-channel](::crossbeam_channel). Sending moves the token (and the bundle) into the
+//! channel, so the sending thread has no way to reach the family afterwards, and getting it back
+//! takes a reply on another channel.
+
+use std::time::Duration;
+
+use ::crossbeam_channel::{bounded, Receiver, RecvError, RecvTimeoutError, SendError, Sender, TryRecvError};
+
+use crate::tokens::TokenWith;
+
+/// A token in transit, along with whatever was sent with it.
+#[derive(Debug)]
+pub struct Handoff<B, U, const ID: usize> {
+    pub token: TokenWith<U, ID>,
+    pub bundle: B,
+}
+
+/// Creates a channel for handing off family `ID`'s token. Only one token of a family exists, so
+/// the channel holds at most one handoff at a time.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, handoff};
+/// let (token, _) = first().unwrap().token();
+/// let (to_worker, worker_inbox) = handoff::channel();
+/// let (to_main, main_inbox) = handoff::channel();
+///
+/// let jobs = vec![Cell::new(1), Cell::new(2)];
+/// to_worker.send(token, jobs).unwrap();
+///
+/// std::thread::spawn(move || {
+///     let mut handoff = worker_inbox.recv().unwrap();
+///     for job in &handoff.bundle {
+///         *job.borrow_mut(&mut handoff.token) *= 10;
+///     }
+///     to_main.send(handoff.token, handoff.bundle).unwrap();
+/// });
+///
+/// let handoff = main_inbox.recv().unwrap();
+/// assert_eq!(*handoff.bundle[1].borrow(&handoff.token), 20);
+/// ```
+///
+/// The sender can't use the token once it's been sent:
+/// ```compile_fail
+/// # use frankencell::{first, Cell, handoff};
+/// let (token, _) = first().unwrap().token();
+/// let (to_worker, _worker_inbox) = handoff::channel::<(), _, 0>();
+/// let cell = Cell::new(0);
+///
+/// to_worker.send(token, ()).unwrap();
+/// cell.borrow(&token);
+/// ```
+pub fn channel<B, U, const ID: usize>() -> (HandoffSender<B, U, ID>, HandoffReceiver<B, U, ID>) {
+    let (sender, receiver) = bounded(1);
+    (HandoffSender { sender }, HandoffReceiver { receiver })
+}
+
+/// The sending half of a [channel].
+pub struct HandoffSender<B, U, const ID: usize> {
+    sender: Sender<Handoff<B, U, ID>>,
+}
+
+impl<B, U, const ID: usize> Clone for HandoffSender<B, U, ID> {
+    fn clone(&self) -> Self {
+        Self { sender: self.sender.clone() }
+    }
+}
+
+impl<B, U, const ID: usize> HandoffSender<B, U, ID> {
+    /// Sends the token and `bundle`. If every receiver is gone, both are given back in the error.
+    pub fn send(&self, token: TokenWith<U, ID>, bundle: B) -> Result<(), SendError<Handoff<B, U, ID>>> {
+        // Never blocks: the family's only token is being sent, so the channel can't be full
+        self.sender.send(Handoff { token, bundle })
+    }
+}
+
+/// The receiving half of a [channel].
+pub struct HandoffReceiver<B, U, const ID: usize> {
+    receiver: Receiver<Handoff<B, U, ID>>,
+}
+
+impl<B, U, const ID: usize> Clone for HandoffReceiver<B, U, ID> {
+    fn clone(&self) -> Self {
+        Self { receiver: self.receiver.clone() }
+    }
+}
+
+impl<B, U, const ID: usize> HandoffReceiver<B, U, ID> {
+    /// Blocks until the token arrives, or fails if every sender is gone.
+    pub fn recv(&self) -> Result<Handoff<B, U, ID>, RecvError> {
+        self.receiver.recv()
+    }
+
+    pub fn try_recv(&self) -> Result<Handoff<B, U, ID>, TryRecvError> {
+        self.receiver.try_recv()
+    }
+
+    pub fn recv_timeout(&self, timeout: Duration) -> Result<Handoff<B, U, ID>, RecvTimeoutError> {
+        self.receiver.recv_timeout(timeout)
+    }
+
+    /// The underlying receiver, for use with [crossbeam_channel::select!](::crossbeam_channel::select).
+    pub fn as_receiver(&self) -> &Receiver<Handoff<B, U, ID>> {
+        &self.receiver
+    }
+}
@@ -0,0 +1,180 @@
+//! [heapless](::heapless)-backed, fixed-capacity collections of token-gated elements, for firmware
+//! that can't allocate. Both can live in a `static` next to the family's other cells.
+
+use ::heapless::{Deque, Vec};
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// A `heapless::Vec` that can be read with a `&Token` and changed with a `&mut Token`. Elements
+/// are reached by position, so they can stand in for the indices an [Arena](crate::arena::Arena)
+/// would hand out when the number of entries is known up front.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, heapless::HeaplessCellVec};
+/// static SENSORS: HeaplessCellVec<u16, 4, 0> = HeaplessCellVec::new();
+///
+/// let (mut token, _) = first().unwrap().token();
+/// SENSORS.push(&mut token, 10).unwrap();
+/// SENSORS.push(&mut token, 20).unwrap();
+///
+/// *SENSORS.get_mut(1, &mut token).unwrap() += 5;
+/// assert_eq!(SENSORS.as_slice(&token), [10, 25]);
+/// ```
+#[repr(transparent)]
+pub struct HeaplessCellVec<T, const N: usize, const ID: usize> {
+    inner: Cell<Vec<T, N>, ID>,
+}
+
+/// A `heapless::Deque` that can be read with a `&Token` and changed with a `&mut Token`.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, heapless::HeaplessCellDeque};
+/// let (mut token, _) = first().unwrap().token();
+/// let events: HeaplessCellDeque<&str, 2, 0> = HeaplessCellDeque::new();
+///
+/// events.push_back(&mut token, "press").unwrap();
+/// events.push_back(&mut token, "release").unwrap();
+/// assert_eq!(events.push_back(&mut token, "press"), Err("press"));
+///
+/// assert_eq!(events.pop_front(&mut token), Some("press"));
+/// assert_eq!(events.len(&token), 1);
+/// ```
+#[repr(transparent)]
+pub struct HeaplessCellDeque<T, const N: usize, const ID: usize> {
+    inner: Cell<Deque<T, N>, ID>,
+}
+
+impl<T, const N: usize, const ID: usize> Default for HeaplessCellVec<T, N, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize, const ID: usize> HeaplessCellVec<T, N, ID> {
+    pub const fn new() -> Self {
+        Self {
+            inner: Cell::new(Vec::new()),
+        }
+    }
+
+    pub fn into_inner(self) -> Vec<T, N> {
+        self.inner.into_inner()
+    }
+
+    pub fn as_slice<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a [T] {
+        self.inner.borrow(token)
+    }
+
+    /// Mutable access to the underlying `heapless::Vec`, for anything not covered by the methods
+    /// below.
+    #[allow(clippy::mut_from_ref)]
+    pub fn as_mut_vec<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut Vec<T, N> {
+        self.inner.borrow_mut(token)
+    }
+
+    pub fn get<'a, U>(&'a self, index: usize, token: &'a TokenWith<U, ID>) -> Option<&'a T> {
+        self.as_slice(token).get(index)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn get_mut<'a, U>(&'a self, index: usize, token: &'a mut TokenWith<U, ID>) -> Option<&'a mut T> {
+        self.as_mut_vec(token).get_mut(index)
+    }
+
+    pub fn len<U>(&self, token: &TokenWith<U, ID>) -> usize {
+        self.inner.borrow(token).len()
+    }
+
+    pub fn is_empty<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.inner.borrow(token).is_empty()
+    }
+
+    pub fn is_full<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.inner.borrow(token).is_full()
+    }
+
+    /// Appends `value`, or hands it back if the vector is full.
+    pub fn push<U>(&self, token: &mut TokenWith<U, ID>, value: T) -> Result<(), T> {
+        self.as_mut_vec(token).push(value)
+    }
+
+    pub fn pop<U>(&self, token: &mut TokenWith<U, ID>) -> Option<T> {
+        self.as_mut_vec(token).pop()
+    }
+
+    /// See [heapless::Vec::truncate](::heapless::Vec::truncate)
+    pub fn truncate<U>(&self, token: &mut TokenWith<U, ID>, len: usize) {
+        self.as_mut_vec(token).truncate(len)
+    }
+
+    pub fn clear<U>(&self, token: &mut TokenWith<U, ID>) {
+        self.as_mut_vec(token).clear()
+    }
+}
+
+impl<T, const N: usize, const ID: usize> Default for HeaplessCellDeque<T, N, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize, const ID: usize> HeaplessCellDeque<T, N, ID> {
+    pub const fn new() -> Self {
+        Self {
+            inner: Cell::new(Deque::new()),
+        }
+    }
+
+    pub fn into_inner(self) -> Deque<T, N> {
+        self.inner.into_inner()
+    }
+
+    /// The elements from front to back, as two contiguous slices.
+    pub fn as_slices<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> (&'a [T], &'a [T]) {
+        self.inner.borrow(token).as_slices()
+    }
+
+    /// Mutable access to the underlying `heapless::Deque`, for anything not covered by the
+    /// methods below.
+    #[allow(clippy::mut_from_ref)]
+    pub fn as_mut_deque<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut Deque<T, N> {
+        self.inner.borrow_mut(token)
+    }
+
+    pub fn len<U>(&self, token: &TokenWith<U, ID>) -> usize {
+        self.inner.borrow(token).len()
+    }
+
+    pub fn is_empty<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.inner.borrow(token).is_empty()
+    }
+
+    pub fn is_full<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.inner.borrow(token).is_full()
+    }
+
+    /// Appends `value` to the back, or hands it back if the deque is full.
+    pub fn push_back<U>(&self, token: &mut TokenWith<U, ID>, value: T) -> Result<(), T> {
+        self.as_mut_deque(token).push_back(value)
+    }
+
+    /// Prepends `value` to the front, or hands it back if the deque is full.
+    pub fn push_front<U>(&self, token: &mut TokenWith<U, ID>, value: T) -> Result<(), T> {
+        self.as_mut_deque(token).push_front(value)
+    }
+
+    pub fn pop_front<U>(&self, token: &mut TokenWith<U, ID>) -> Option<T> {
+        self.as_mut_deque(token).pop_front()
+    }
+
+    pub fn pop_back<U>(&self, token: &mut TokenWith<U, ID>) -> Option<T> {
+        self.as_mut_deque(token).pop_back()
+    }
+
+    pub fn clear<U>(&self, token: &mut TokenWith<U, ID>) {
+        self.as_mut_deque(token).clear()
+    }
+}
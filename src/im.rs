@@ -0,0 +1,95 @@
+//! Token-gated updates for [im](::im)'s persistent collections stored in a [Cell]. Every update
+//! builds the new version with structural sharing, so a snapshot taken before it is a cheap
+//! clone that stays unchanged.
+
+use std::hash::{BuildHasher, Hash};
+
+use ::im::{HashMap, Vector};
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+impl<A: Clone, const ID: usize> Cell<Vector<A>, ID> {
+    /// A cheap copy of the current version, which later updates don't affect.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let history: Cell<im::Vector<u32>, 0> = Cell::new(im::vector![1, 2, 3]);
+    ///
+    /// let before = history.snapshot(&token);
+    /// history.update(&mut token, 0, 10);
+    /// history.push_back(&mut token, 4);
+    ///
+    /// assert_eq!(before, im::vector![1, 2, 3]);
+    /// assert_eq!(*history.borrow(&token), im::vector![10, 2, 3, 4]);
+    ///
+    /// history.restore(&mut token, before);
+    /// assert_eq!(history.borrow(&token).len(), 3);
+    /// ```
+    pub fn snapshot<U>(&self, token: &TokenWith<U, ID>) -> Vector<A> {
+        self.borrow(token).clone()
+    }
+
+    /// Replaces the element at `index`. See [Vector::update].
+    ///
+    /// # Panics
+    /// Panics if `index` is out of bounds.
+    pub fn update<U>(&self, token: &mut TokenWith<U, ID>, index: usize, value: A) {
+        let vector = self.borrow_mut(token);
+        *vector = vector.update(index, value);
+    }
+
+    pub fn push_back<U>(&self, token: &mut TokenWith<U, ID>, value: A) {
+        self.borrow_mut(token).push_back(value)
+    }
+
+    /// Goes back to a version taken with [Self::snapshot], returning the current one.
+    pub fn restore<U>(&self, token: &mut TokenWith<U, ID>, snapshot: Vector<A>) -> Vector<A> {
+        std::mem::replace(self.borrow_mut(token), snapshot)
+    }
+}
+
+impl<K, V, S, const ID: usize> Cell<HashMap<K, V, S>, ID>
+where
+    K: Hash + Eq + Clone,
+    V: Clone,
+    S: BuildHasher + Clone,
+{
+    /// A cheap copy of the current version, which later updates don't affect.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let scores: Cell<im::HashMap<&str, u32>, 0> = Cell::new(im::HashMap::new());
+    ///
+    /// scores.update(&mut token, "a", 1);
+    /// let before = scores.snapshot(&token);
+    /// scores.update(&mut token, "a", 2);
+    /// scores.remove(&mut token, &"a");
+    ///
+    /// assert_eq!(before.get("a"), Some(&1));
+    /// assert!(scores.borrow(&token).is_empty());
+    /// ```
+    pub fn snapshot<U>(&self, token: &TokenWith<U, ID>) -> HashMap<K, V, S> {
+        self.borrow(token).clone()
+    }
+
+    /// Inserts or replaces the value for `key`. See [HashMap::update].
+    pub fn update<U>(&self, token: &mut TokenWith<U, ID>, key: K, value: V) {
+        let map = self.borrow_mut(token);
+        *map = map.update(key, value);
+    }
+
+    /// Removes `key`, returning its value. See [HashMap::remove].
+    pub fn remove<U>(&self, token: &mut TokenWith<U, ID>, key: &K) -> Option<V> {
+        self.borrow_mut(token).remove(key)
+    }
+
+    /// Goes back to a version taken with [Self::snapshot], returning the current one.
+    pub fn restore<U>(&self, token: &mut TokenWith<U, ID>, snapshot: HashMap<K, V, S>) -> HashMap<K, V, S> {
+        std::mem::replace(self.borrow_mut(token), snapshot)
+    }
+}
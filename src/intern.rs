@@ -0,0 +1,84 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use crate::arena::{Arena, SmallWeakIndex};
+use crate::tokens::TokenWith;
+
+/// An interned string: a small `Copy` handle that compares, hashes and orders in constant time.
+/// Turned back into a `&str` with [Interner::resolve].
+#[derive(Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Debug)]
+pub struct Symbol<const ID: usize>(SmallWeakIndex<Arc<str>, ID>);
+
+/// Deduplicates strings into an [Arena], handing out a [Symbol] for each distinct string.
+/// Resolving a symbol only needs a shared token, so symbols can be resolved everywhere the
+/// family's token is available while new strings are interned wherever the interner itself is
+/// owned.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, intern::Interner};
+/// let (token, _) = first().unwrap().token();
+/// let mut names = Interner::new();
+///
+/// let a = names.intern("main");
+/// let b = names.intern("helper");
+/// assert_eq!(names.intern("main"), a);
+/// assert_ne!(a, b);
+///
+/// assert_eq!(names.resolve(b, &token), "helper");
+/// assert_eq!(names.get("missing"), None);
+/// assert_eq!(names.len(), 2);
+/// ```
+pub struct Interner<const ID: usize> {
+    symbols: HashMap<Arc<str>, Symbol<ID>>,
+    strings: Arena<Arc<str>, ID>,
+}
+
+impl<const ID: usize> Default for Interner<ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const ID: usize> Interner<ID> {
+    pub fn new() -> Self {
+        Self {
+            symbols: HashMap::new(),
+            strings: Arena::new(),
+        }
+    }
+
+    /// The number of distinct strings interned so far.
+    pub fn len(&self) -> usize {
+        self.strings.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.strings.is_empty()
+    }
+
+    /// Returns the symbol for `s`, interning it first if needed.
+    pub fn intern(&mut self, s: &str) -> Symbol<ID> {
+        if let Some(&symbol) = self.symbols.get(s) {
+            return symbol;
+        }
+
+        let string: Arc<str> = s.into();
+        let symbol = Symbol(self.strings.insert(Arc::clone(&string)).compact().downgrade());
+        self.symbols.insert(string, symbol);
+        symbol
+    }
+
+    /// Returns the symbol for `s` if it has already been interned.
+    pub fn get(&self, s: &str) -> Option<Symbol<ID>> {
+        self.symbols.get(s).copied()
+    }
+
+    /// # Panics
+    /// Panics if `symbol` came from a different interner. Without the `arena-brand` feature, a
+    /// symbol from another interner in the same family may instead resolve to the wrong string.
+    pub fn resolve<'a, U>(&'a self, symbol: Symbol<ID>, token: &'a TokenWith<U, ID>) -> &'a str {
+        let index = self.strings.upgrade(symbol.0).expect("Symbol used with the wrong Interner");
+        self.strings.get(&index, token)
+    }
+}
@@ -0,0 +1,101 @@
+use core::cell::UnsafeCell;
+
+use critical_section::CriticalSection;
+
+use crate::tokens::TokenWith;
+
+/// A family's token, stored in a `static` so that it can be shared between the main loop and
+/// interrupt handlers, but only handed out inside a critical section. Combined with `static`
+/// [Cell](crate::Cell)s of the same family, this replaces the usual `Mutex<RefCell<T>>`: the
+/// only runtime check left is a flag catching reentrant calls to [Self::lock].
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, interrupt::InterruptToken};
+/// static TOKEN: InterruptToken<(), 0> = InterruptToken::new();
+/// static TICKS: Cell<u32, 0> = Cell::new(0);
+///
+/// // In an interrupt handler
+/// fn on_tick() {
+///     critical_section::with(|cs| TOKEN.lock(cs, |token| *TICKS.borrow_mut(token) += 1));
+/// }
+///
+/// let (token, _) = first().unwrap().token();
+/// critical_section::with(|cs| TOKEN.install(cs, token));
+///
+/// on_tick();
+/// on_tick();
+/// assert_eq!(TOKEN.with(|token| *TICKS.borrow(token)), 2);
+/// ```
+pub struct InterruptToken<U, const ID: usize> {
+    token: UnsafeCell<Option<TokenWith<U, ID>>>,
+    locked: UnsafeCell<bool>,
+}
+
+// Safety: the contents are only ever accessed inside a critical section
+unsafe impl<U: Send, const ID: usize> Sync for InterruptToken<U, ID> {}
+
+impl<U, const ID: usize> Default for InterruptToken<U, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<U, const ID: usize> InterruptToken<U, ID> {
+    /// Creates an empty `InterruptToken`. A token has to be moved in with [Self::install] before
+    /// [Self::lock] can be used.
+    pub const fn new() -> Self {
+        Self {
+            token: UnsafeCell::new(None),
+            locked: UnsafeCell::new(false),
+        }
+    }
+
+    /// Moves `token` in, returning the previously installed token if there was one.
+    ///
+    /// # Panics
+    /// Panics if called from inside [Self::lock].
+    pub fn install(&self, _cs: CriticalSection<'_>, token: TokenWith<U, ID>) -> Option<TokenWith<U, ID>> {
+        assert!(!unsafe {*self.locked.get()}, "InterruptToken installed while locked");
+        unsafe {(*self.token.get()).replace(token)}
+    }
+
+    /// Moves the token back out, if there is one.
+    ///
+    /// # Panics
+    /// Panics if called from inside [Self::lock].
+    pub fn take(&self, _cs: CriticalSection<'_>) -> Option<TokenWith<U, ID>> {
+        assert!(!unsafe {*self.locked.get()}, "InterruptToken taken while locked");
+        unsafe {(*self.token.get()).take()}
+    }
+
+    /// Lends the token to `f` for the duration of the critical section.
+    ///
+    /// # Panics
+    /// Panics if no token has been installed, or if called from inside another call to `lock` on
+    /// the same `InterruptToken`.
+    pub fn lock<R>(&self, _cs: CriticalSection<'_>, f: impl FnOnce(&mut TokenWith<U, ID>) -> R) -> R {
+        // Safety: we are inside a critical section, so nothing else can be accessing `self`
+        unsafe {
+            assert!(!*self.locked.get(), "InterruptToken locked reentrantly");
+            let token = (*self.token.get()).as_mut().expect("InterruptToken used before install");
+
+            *self.locked.get() = true;
+            let _unlock = Unlock(&self.locked);
+            f(token)
+        }
+    }
+
+    /// Enters a critical section and calls [Self::lock].
+    pub fn with<R>(&self, f: impl FnOnce(&mut TokenWith<U, ID>) -> R) -> R {
+        critical_section::with(|cs| self.lock(cs, f))
+    }
+}
+
+struct Unlock<'a>(&'a UnsafeCell<bool>);
+
+impl Drop for Unlock<'_> {
+    fn drop(&mut self) {
+        unsafe {*self.0.get() = false;}
+    }
+}
@@ -0,0 +1,35 @@
+use std::io::Cursor;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// `std::io` adapters for token-gated byte buffers, so they can be handed directly to serializers,
+/// compressors, etc.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell};
+/// use std::io::{Read, Write};
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let buffer = Cell::new(Vec::new());
+///
+/// write!(buffer.writer(&mut token), "Hello {}!", "World").unwrap();
+///
+/// let mut s = String::new();
+/// buffer.reader(&token).read_to_string(&mut s).unwrap();
+/// assert_eq!(s, "Hello World!");
+/// ```
+impl<const ID: usize> Cell<Vec<u8>, ID> {
+    /// Returns an [io::Read](std::io::Read) + [io::BufRead](std::io::BufRead) +
+    /// [io::Seek](std::io::Seek) over the current contents of the buffer.
+    pub fn reader<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> Cursor<&'a [u8]> {
+        Cursor::new(self.borrow(token).as_slice())
+    }
+
+    /// Returns an [io::Write](std::io::Write) that appends to the buffer.
+    #[allow(clippy::mut_from_ref)]
+    pub fn writer<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut Vec<u8> {
+        self.borrow_mut(token)
+    }
+}
@@ -0,0 +1,103 @@
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// Adapters for iterators over `&Cell<T, ID>`, removing the `.map(|c| c.borrow(&token))`
+/// boilerplate.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, iter::IterWith};
+/// let (mut token, _) = first().unwrap().token();
+/// let cells = vec![Cell::new(1), Cell::new(2), Cell::new(3)];
+///
+/// let mut doubled = cells.iter().borrowed_mut(&mut token);
+/// while let Some(v) = doubled.next() {
+///     *v *= 2;
+/// }
+///
+/// let sum: i32 = cells.iter().borrowed(&token).sum();
+/// assert_eq!(sum, 12);
+/// ```
+pub trait IterWith<'c, T: 'c, const ID: usize>: Iterator<Item = &'c Cell<T, ID>> + Sized {
+    /// Yields a `&T` for every cell.
+    fn borrowed<'t, U>(self, token: &'t TokenWith<U, ID>) -> Borrowed<'t, Self, U, ID>
+        where 'c: 't
+    {
+        Borrowed { iter: self, token }
+    }
+
+    /// Yields a `&mut T` for every cell.
+    ///
+    /// Nothing stops an iterator from yielding the same cell twice, so the result can't be an
+    /// [Iterator]: each `&mut T` returned by [BorrowedMut::next] must be dropped before the next
+    /// one is requested.
+    fn borrowed_mut<'t, U>(self, token: &'t mut TokenWith<U, ID>) -> BorrowedMut<'t, Self, U, ID>
+        where 'c: 't
+    {
+        BorrowedMut { iter: self, token }
+    }
+}
+
+impl<'c, T: 'c, I: Iterator<Item = &'c Cell<T, ID>>, const ID: usize> IterWith<'c, T, ID> for I {}
+
+/// Wraps every item of an iterator in a [Cell].
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, iter::CollectCells};
+/// let (token, _) = first().unwrap().token();
+///
+/// let cells = (1..=3).collect_cells::<0>();
+/// let words: Cell<Vec<&str>, 0> = "a b c".split(' ').collect();
+///
+/// assert_eq!(*cells[2].borrow(&token), 3);
+/// assert_eq!(words.borrow(&token).len(), 3);
+/// ```
+pub trait CollectCells: Iterator + Sized {
+    fn collect_cells<const ID: usize>(self) -> Vec<Cell<Self::Item, ID>> {
+        self.map(Cell::new).collect()
+    }
+}
+
+impl<I: Iterator> CollectCells for I {}
+
+/// See [IterWith::borrowed]
+pub struct Borrowed<'t, I, U, const ID: usize> {
+    iter: I,
+    token: &'t TokenWith<U, ID>,
+}
+
+impl<'c: 't, 't, T: 'c, I, U, const ID: usize> Iterator for Borrowed<'t, I, U, ID>
+    where I: Iterator<Item = &'c Cell<T, ID>>
+{
+    type Item = &'t T;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        self.iter.next().map(|cell| cell.borrow(self.token))
+    }
+
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        self.iter.size_hint()
+    }
+}
+
+/// See [IterWith::borrowed_mut]
+pub struct BorrowedMut<'t, I, U, const ID: usize> {
+    iter: I,
+    token: &'t mut TokenWith<U, ID>,
+}
+
+impl<'c: 't, 't, T: 'c, I, U, const ID: usize> BorrowedMut<'t, I, U, ID>
+    where I: Iterator<Item = &'c Cell<T, ID>>
+{
+    #[allow(clippy::should_implement_trait)]
+    pub fn next(&mut self) -> Option<&mut T> {
+        self.iter.next().map(|cell| cell.borrow_mut(self.token))
+    }
+
+    pub fn for_each(mut self, mut f: impl FnMut(&mut T)) {
+        while let Some(v) = self.next() {
+            f(v);
+        }
+    }
+}
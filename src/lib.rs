@@ -1,5 +1,8 @@
 #![allow(incomplete_features)]
+#![cfg_attr(feature = "allocator-api", feature(allocator_api))]
+#![cfg_attr(feature = "no-self-token", feature(auto_traits))]
 #![feature(generic_const_exprs)]
+#![cfg_attr(feature = "no-self-token", feature(negative_impls))]
 
 //! # Purpose
 //! This crate is another attempt at the `ghost-cell` / `qcell` saga of cell crates. This provides
@@ -73,6 +76,12 @@
 //! It may also be possible with macros when/if macros are allowed to keep a local state
 //! (rust-lang/rust issue 44034).
 //!
+//! The same limitation rules out per-thread families, e.g. a `thread_first()` handing each thread
+//! its own range of IDs. A range assigned at runtime can't be reflected in the returned
+//! `TokenBuilder`'s type, and giving every thread a `TokenBuilder<0>` would let two threads hold a
+//! `Token<0>` for the same (`Sync`) cells. For now, tokens for worker threads should be built from
+//! the single [first()] chain, for example with [init_tokens!], and moved into each thread.
+//!
 //! # Should I use this? 
 //! Probably not. At the moment this is really more of a proof-of-concept. There's still a lot of
 //! work that needs to go into the compiler and, even then, this may not be a viable solution.
@@ -80,17 +89,101 @@
 //! If you're simply looking for something that's more ergonomic than `ghost-cell` and `qcell`, the
 //! `cell-family` crate seems to have a good approach.
 
+pub mod alloc;
+pub mod any_arena;
+pub mod arena;
+#[cfg(feature = "arrayvec")]
+pub mod arrayvec;
+pub mod audit;
+pub mod bitset;
+pub mod boxed;
+pub mod brand;
+pub mod buffer;
 mod builder;
+pub mod bump;
 pub mod cells;
+pub mod cow;
+pub mod diff;
+pub mod drop_queue;
+#[cfg(feature = "crossbeam-epoch")]
+pub mod epoch;
+#[cfg(feature = "ffi")]
+pub mod ffi;
+pub mod frozen;
+pub mod global;
+pub mod grid;
+#[cfg(feature = "crossbeam-channel")]
+pub mod handoff;
+#[cfg(feature = "heapless")]
+pub mod heapless;
+#[cfg(feature = "im")]
+pub mod im;
+pub mod intern;
+#[cfg(feature = "critical-section")]
+pub mod interrupt;
+pub mod io;
+pub mod iter;
+pub mod lock;
+pub mod lru;
+pub mod merge;
+#[cfg(feature = "ndarray")]
+pub mod ndarray;
+pub mod padded;
+mod paranoid;
+#[cfg(feature = "serde")]
+pub mod persist;
+#[cfg(feature = "petgraph")]
+pub mod petgraph;
+pub mod phase;
+pub mod pool;
+pub mod prelude;
+pub mod rc;
+pub mod registry;
+pub mod result;
+#[cfg(feature = "rtic")]
+pub mod rtic;
+pub mod scoped;
+pub mod seqlock;
+pub mod shared;
+pub mod slice;
+#[cfg(feature = "smallvec")]
+pub mod smallvec;
+#[doc(hidden)]
+pub mod split;
+pub mod spsc;
+pub mod state;
+pub mod stats;
+pub mod string;
+mod sync;
+#[cfg(feature = "tokio")]
+pub mod task;
 pub mod tokens;
+pub mod typemap;
+pub mod watch;
+#[cfg(feature = "zeroize")]
+pub mod zeroize;
+
+use std::fmt::Display;
+use std::panic::Location;
+use std::ptr;
 
-use std::sync::Once;
+use crate::sync::{ClaimFlag, Ordering};
 
 pub use crate::builder::TokenBuilder;
 pub use crate::cells::*;
 pub use crate::tokens::*;
+pub use crate::stats::stats;
+#[cfg(feature = "derive")]
+pub use frankencell_derive::Tokened;
 
-static FIRST: Once = Once::new();
+// Where `first()` was called successfully, or null if it hasn't been yet
+#[cfg(not(loom))]
+static CLAIMED_AT: ClaimFlag = ClaimFlag::new(ptr::null_mut());
+
+#[cfg(loom)]
+loom::lazy_static! {
+    static ref CLAIMED_AT: ClaimFlag = ClaimFlag::new(ptr::null_mut());
+}
 
 /// Entry-point into the API that allows for safe creation of unique `Token`s.
 ///
@@ -100,15 +193,71 @@ static FIRST: Once = Once::new();
 /// assert!(first().is_none());
 /// ```
 // Implementation stolen lovingly from LegionMammal978
+#[track_caller]
 pub fn first() -> Option<TokenBuilder<0>> {
-    let mut builder = None;
-    FIRST.call_once(|| {
-        builder = Some(unsafe { TokenBuilder::new() });
-    });
+    try_first().ok()
+}
+
+/// Same as [first()], but on failure reports where the first `TokenBuilder` was claimed.
+///
+/// ```rust
+/// # use frankencell::try_first;
+/// let builder = try_first().unwrap();
+///
+/// let err = try_first().err().unwrap();
+/// assert_eq!(err.claimed_at().line(), line!() - 3);
+/// println!("{err}");
+/// ```
+#[track_caller]
+pub fn try_first() -> Result<TokenBuilder<0>, AlreadyClaimed> {
+    let here = Location::caller() as *const Location<'static> as *mut Location<'static>;
+
+    match CLAIMED_AT.compare_exchange(ptr::null_mut(), here, Ordering::AcqRel, Ordering::Acquire) {
+        Ok(_) => {
+            #[cfg(feature = "tracing")]
+            tracing::debug!(at = %Location::caller(), "claimed first TokenBuilder");
+
+            Ok(unsafe { TokenBuilder::new() })
+        }
+        Err(claimed_at) => {
+            let err = AlreadyClaimed {
+                claimed_at: unsafe { &*claimed_at },
+            };
+
+            #[cfg(feature = "tracing")]
+            tracing::warn!(at = %Location::caller(), "{err}");
 
-    builder
+            Err(err)
+        }
+    }
+}
+
+// Where the first `TokenBuilder` was claimed, if it has been
+pub(crate) fn claimed_at() -> Option<&'static Location<'static>> {
+    unsafe {CLAIMED_AT.load(Ordering::Acquire).as_ref()}
 }
 
+/// Returned by [try_first()] when the first `TokenBuilder` has already been claimed.
+#[derive(Clone, Copy, Debug)]
+pub struct AlreadyClaimed {
+    claimed_at: &'static Location<'static>,
+}
+
+impl AlreadyClaimed {
+    /// Where [first()] or [try_first()] was first called.
+    pub fn claimed_at(&self) -> &'static Location<'static> {
+        self.claimed_at
+    }
+}
+
+impl Display for AlreadyClaimed {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "the first TokenBuilder was already claimed at {}", self.claimed_at)
+    }
+}
+
+impl std::error::Error for AlreadyClaimed {}
+
 /// Slightly more convenient way to initialize multiple tokens. Note that this currently only
 /// supports the basic [Token](crate::tokens::Token) type, and a [TokenWith] must be built manually
 /// 
@@ -130,7 +279,7 @@ macro_rules! init_tokens {
 
 #[test]
 fn init_tokens_test() {
-    use crate::{TokenBuilder, init_tokens, Cell};
+    use crate::{TokenBuilder, Cell};
 
     let first = unsafe {TokenBuilder::<0>::new()};
     init_tokens! { after first;
@@ -151,5 +300,10 @@ fn test_first() {
     use crate::first;
 
     assert!(first().is_some());
+    let line = line!() - 1;
     assert!(first().is_none());
+
+    let err = crate::try_first().err().unwrap();
+    assert_eq!(err.claimed_at().line(), line);
+    assert_eq!(err.claimed_at().file(), file!());
 }
@@ -0,0 +1,421 @@
+//! Tokens behind a lock, for families that are shared between threads without a fixed owner.
+//!
+//! As with [std::sync::Mutex], a panic while a guard is held poisons the lock: since the
+//! panicking thread may have left the family's cells half-updated, later attempts to lock it
+//! return an `Err` (which still contains the guard). Once the cells have been checked or repaired,
+//! the poison can be removed with `clear_poison`.
+
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+use std::time::{Duration, Instant};
+use std::sync::{Condvar, LockResult, Mutex, MutexGuard, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard, TryLockError, TryLockResult};
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+pub mod async_lock;
+mod order;
+#[cfg(feature = "parking_lot")]
+pub mod parking_lot;
+
+fn map_result<G, H>(result: LockResult<G>, f: impl FnOnce(G) -> H) -> LockResult<H> {
+    match result {
+        Ok(guard) => Ok(f(guard)),
+        Err(poisoned) => Err(PoisonError::new(f(poisoned.into_inner()))),
+    }
+}
+
+fn map_try_result<G, H>(result: TryLockResult<G>, f: impl FnOnce(G) -> H) -> TryLockResult<H> {
+    match result {
+        Ok(guard) => Ok(f(guard)),
+        Err(TryLockError::Poisoned(poisoned)) => Err(TryLockError::Poisoned(PoisonError::new(f(poisoned.into_inner())))),
+        Err(TryLockError::WouldBlock) => Err(TryLockError::WouldBlock),
+    }
+}
+
+// std's locks can't block with a timeout, so this polls `attempt`, backing off from yielding to
+// sleeping for up to a millisecond at a time
+fn poll_for<G>(timeout: Duration, mut attempt: impl FnMut() -> TryLockResult<G>) -> TryLockResult<G> {
+    let start = Instant::now();
+    let mut backoff = Duration::from_micros(1);
+
+    loop {
+        match attempt() {
+            Err(TryLockError::WouldBlock) => {}
+            result => return result,
+        }
+
+        let elapsed = start.elapsed();
+        if elapsed >= timeout {
+            return Err(TryLockError::WouldBlock);
+        }
+
+        if backoff < Duration::from_micros(16) {
+            std::thread::yield_now();
+        } else {
+            std::thread::sleep(backoff.min(timeout - elapsed));
+        }
+        backoff = (backoff * 2).min(Duration::from_millis(1));
+    }
+}
+
+/// Returned by the parking_lot adapters' timed acquisitions when the token wasn't available in
+/// time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct LockTimeout {
+    timeout: Duration,
+}
+
+impl LockTimeout {
+    #[cfg_attr(not(feature = "parking_lot"), allow(dead_code))]
+    pub(crate) fn new(timeout: Duration) -> Self {
+        Self { timeout }
+    }
+
+    /// How long the acquisition waited.
+    pub fn timeout(&self) -> Duration {
+        self.timeout
+    }
+}
+
+impl std::fmt::Display for LockTimeout {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "token lock not acquired within {:?}", self.timeout)
+    }
+}
+
+impl std::error::Error for LockTimeout {}
+
+/// A token behind a mutex. The guard derefs to the token, so it can be passed directly to
+/// [Cell::borrow](crate::Cell::borrow) and [Cell::borrow_mut](crate::Cell::borrow_mut).
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, lock::TokenMutex};
+/// let (token, _) = first().unwrap().token();
+/// let token = TokenMutex::new(token);
+/// let counter = Cell::new(0);
+///
+/// let result = std::thread::scope(|s| s.spawn(|| {
+///     let mut token = token.lock().unwrap();
+///     *counter.borrow_mut(&mut token) += 1;
+///     panic!("oops");
+/// }).join());
+/// assert!(result.is_err());
+///
+/// assert!(token.is_poisoned());
+/// let mut guard = token.lock().unwrap_err().into_inner();
+/// assert_eq!(counter.borrow(&guard), &1);
+/// counter.set(&mut guard, 0);
+/// drop(guard);
+///
+/// token.clear_poison();
+/// assert!(token.lock().is_ok());
+/// ```
+pub struct TokenMutex<U, const ID: usize> {
+    inner: Mutex<TokenWith<U, ID>>,
+}
+
+/// Exclusive access to a [TokenMutex]'s token.
+pub struct TokenMutexGuard<'a, U, const ID: usize> {
+    guard: MutexGuard<'a, TokenWith<U, ID>>,
+    _held: order::Held,
+}
+
+impl<U, const ID: usize> TokenMutex<U, ID> {
+    pub const fn new(token: TokenWith<U, ID>) -> Self {
+        Self {
+            inner: Mutex::new(token),
+        }
+    }
+
+    /// Blocks until the token is available. Returns an `Err` containing the guard if the lock is
+    /// poisoned.
+    pub fn lock(&self) -> LockResult<TokenMutexGuard<'_, U, ID>> {
+        order::check(ID);
+        map_result(self.inner.lock(), |guard| TokenMutexGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    pub fn try_lock(&self) -> TryLockResult<TokenMutexGuard<'_, U, ID>> {
+        map_try_result(self.inner.try_lock(), |guard| TokenMutexGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    /// Like [Self::lock], but gives up after `timeout`, returning [TryLockError::WouldBlock].
+    /// std's mutex can't block with a timeout, so this retries [Self::try_lock] with a short
+    /// backoff instead.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, lock::TokenMutex};
+    /// # use std::sync::TryLockError;
+    /// # use std::time::Duration;
+    /// let (token, _) = first().unwrap().token();
+    /// let token = TokenMutex::new(token);
+    ///
+    /// let held = token.lock().unwrap();
+    /// std::thread::scope(|s| s.spawn(|| {
+    ///     assert!(matches!(token.try_lock_for(Duration::from_millis(5)), Err(TryLockError::WouldBlock)));
+    /// }).join().unwrap());
+    ///
+    /// drop(held);
+    /// assert!(token.try_lock_for(Duration::from_millis(5)).is_ok());
+    /// ```
+    pub fn try_lock_for(&self, timeout: Duration) -> TryLockResult<TokenMutexGuard<'_, U, ID>> {
+        order::check(ID);
+        poll_for(timeout, || self.try_lock())
+    }
+
+    /// Whether a thread panicked while holding the token.
+    pub fn is_poisoned(&self) -> bool {
+        self.inner.is_poisoned()
+    }
+
+    pub fn clear_poison(&self) {
+        self.inner.clear_poison();
+    }
+
+    pub fn into_inner(self) -> LockResult<TokenWith<U, ID>> {
+        self.inner.into_inner()
+    }
+}
+
+impl<U: Debug, const ID: usize> Debug for TokenMutexGuard<'_, U, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<U, const ID: usize> Deref for TokenMutexGuard<'_, U, ID> {
+    type Target = TokenWith<U, ID>;
+
+    fn deref(&self) -> &TokenWith<U, ID> {
+        &self.guard
+    }
+}
+
+impl<U, const ID: usize> DerefMut for TokenMutexGuard<'_, U, ID> {
+    fn deref_mut(&mut self) -> &mut TokenWith<U, ID> {
+        &mut self.guard
+    }
+}
+
+/// A token behind a reader-writer lock, so that several threads can read the family's cells at
+/// once. Read guards deref to `&TokenWith` and write guards to `&mut TokenWith`.
+///
+/// Poisoning works the same way as for [TokenMutex], except that only a panic while holding a
+/// write guard poisons the lock.
+pub struct TokenRwLock<U, const ID: usize> {
+    inner: RwLock<TokenWith<U, ID>>,
+}
+
+/// Shared access to a [TokenRwLock]'s token.
+pub struct TokenReadGuard<'a, U, const ID: usize> {
+    guard: RwLockReadGuard<'a, TokenWith<U, ID>>,
+    _held: order::Held,
+}
+
+/// Exclusive access to a [TokenRwLock]'s token.
+pub struct TokenWriteGuard<'a, U, const ID: usize> {
+    guard: RwLockWriteGuard<'a, TokenWith<U, ID>>,
+    _held: order::Held,
+}
+
+impl<U, const ID: usize> TokenRwLock<U, ID> {
+    pub const fn new(token: TokenWith<U, ID>) -> Self {
+        Self {
+            inner: RwLock::new(token),
+        }
+    }
+
+    pub fn read(&self) -> LockResult<TokenReadGuard<'_, U, ID>> {
+        order::check(ID);
+        map_result(self.inner.read(), |guard| TokenReadGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    pub fn try_read(&self) -> TryLockResult<TokenReadGuard<'_, U, ID>> {
+        map_try_result(self.inner.try_read(), |guard| TokenReadGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    pub fn write(&self) -> LockResult<TokenWriteGuard<'_, U, ID>> {
+        order::check(ID);
+        map_result(self.inner.write(), |guard| TokenWriteGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    pub fn try_write(&self) -> TryLockResult<TokenWriteGuard<'_, U, ID>> {
+        map_try_result(self.inner.try_write(), |guard| TokenWriteGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    /// Like [Self::read], but gives up after `timeout`. See [TokenMutex::try_lock_for].
+    pub fn try_read_for(&self, timeout: Duration) -> TryLockResult<TokenReadGuard<'_, U, ID>> {
+        order::check(ID);
+        poll_for(timeout, || self.try_read())
+    }
+
+    /// Like [Self::write], but gives up after `timeout`. See [TokenMutex::try_lock_for].
+    pub fn try_write_for(&self, timeout: Duration) -> TryLockResult<TokenWriteGuard<'_, U, ID>> {
+        order::check(ID);
+        poll_for(timeout, || self.try_write())
+    }
+
+    /// Whether a thread panicked while holding a write guard.
+    pub fn is_poisoned(&self) -> bool {
+        self.inner.is_poisoned()
+    }
+
+    pub fn clear_poison(&self) {
+        self.inner.clear_poison();
+    }
+
+    pub fn into_inner(self) -> LockResult<TokenWith<U, ID>> {
+        self.inner.into_inner()
+    }
+}
+
+impl<U: Debug, const ID: usize> Debug for TokenReadGuard<'_, U, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<U, const ID: usize> Deref for TokenReadGuard<'_, U, ID> {
+    type Target = TokenWith<U, ID>;
+
+    fn deref(&self) -> &TokenWith<U, ID> {
+        &self.guard
+    }
+}
+
+impl<U: Debug, const ID: usize> Debug for TokenWriteGuard<'_, U, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<U, const ID: usize> Deref for TokenWriteGuard<'_, U, ID> {
+    type Target = TokenWith<U, ID>;
+
+    fn deref(&self) -> &TokenWith<U, ID> {
+        &self.guard
+    }
+}
+
+impl<U, const ID: usize> DerefMut for TokenWriteGuard<'_, U, ID> {
+    fn deref_mut(&mut self) -> &mut TokenWith<U, ID> {
+        &mut self.guard
+    }
+}
+
+/// A condition variable for waiting on a cell guarded by a [TokenMutex]. Waiting releases the
+/// token so another thread can lock it, change the cell and call [Self::notify_one] or
+/// [Self::notify_all].
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, lock::{TokenCondvar, TokenMutex}};
+/// let (token, _) = first().unwrap().token();
+/// let token = TokenMutex::new(token);
+/// let ready = TokenCondvar::new();
+/// let jobs = Cell::new(Vec::new());
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         jobs.borrow_mut(&mut token.lock().unwrap()).push("job");
+///         ready.notify_all();
+///     });
+///
+///     let mut guard = ready.wait_while(token.lock().unwrap(), &jobs, |jobs| jobs.is_empty()).unwrap();
+///     assert_eq!(jobs.borrow_mut(&mut guard).pop(), Some("job"));
+/// });
+/// ```
+#[derive(Debug, Default)]
+pub struct TokenCondvar {
+    inner: Condvar,
+}
+
+impl TokenCondvar {
+    pub const fn new() -> Self {
+        Self {
+            inner: Condvar::new(),
+        }
+    }
+
+    /// Releases the token and blocks until notified, then locks it again. Like
+    /// [Condvar::wait], this can wake up spuriously.
+    pub fn wait<'a, U, const ID: usize>(&self, guard: TokenMutexGuard<'a, U, ID>) -> LockResult<TokenMutexGuard<'a, U, ID>> {
+        order::check(ID);
+        map_result(self.inner.wait(guard.guard), |guard| TokenMutexGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    /// Blocks until `condition` returns false for the contents of `cell`. The condition is checked
+    /// with the token locked, before waiting and after every wakeup.
+    pub fn wait_while<'a, T, U, const ID: usize>(
+        &self,
+        guard: TokenMutexGuard<'a, U, ID>,
+        cell: &Cell<T, ID>,
+        mut condition: impl FnMut(&mut T) -> bool,
+    ) -> LockResult<TokenMutexGuard<'a, U, ID>> {
+        order::check(ID);
+        let result = self.inner.wait_while(guard.guard, |token| condition(cell.borrow_mut(token)));
+        map_result(result, |guard| TokenMutexGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    pub fn notify_one(&self) {
+        self.inner.notify_one();
+    }
+
+    pub fn notify_all(&self) {
+        self.inner.notify_all();
+    }
+}
+
+#[test]
+fn rwlock_poison_test() {
+    use crate::{Cell, TokenBuilder};
+
+    let (token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let token = TokenRwLock::new(token);
+    let cell = Cell::new(1);
+
+    // Readers panicking doesn't poison
+    let _ = std::thread::scope(|s| s.spawn(|| {
+        let _guard = token.read().unwrap();
+        panic!();
+    }).join());
+    assert!(!token.is_poisoned());
+
+    let _ = std::thread::scope(|s| s.spawn(|| {
+        let mut guard = token.write().unwrap();
+        cell.set(&mut guard, 2);
+        panic!();
+    }).join());
+    assert!(token.is_poisoned());
+    assert!(matches!(token.try_read(), Err(TryLockError::Poisoned(_))));
+
+    token.clear_poison();
+    assert_eq!(cell.borrow(&token.read().unwrap()), &2);
+}
+
+#[cfg(all(feature = "deadlock-detection", debug_assertions))]
+#[test]
+fn lock_order_cycle_test() {
+    use crate::TokenBuilder;
+
+    let (a, next) = unsafe {TokenBuilder::<1010>::new()}.token();
+    let (b, _) = next.token();
+    let (a, b) = (TokenMutex::new(a), TokenMutex::new(b));
+
+    {
+        let _a = a.lock().unwrap();
+        let _b = b.lock().unwrap();
+    }
+
+    // Taking them one at a time is fine
+    drop(b.lock().unwrap());
+    drop(a.lock().unwrap());
+
+    let result = std::panic::catch_unwind(|| {
+        let _b = b.lock().unwrap();
+        let _a = a.lock().unwrap();
+    });
+    assert!(result.is_err());
+}
@@ -0,0 +1,356 @@
+//! Async locks for tokens that don't depend on any runtime, so they work the same under tokio,
+//! smol, async-std or embassy. Waiting tasks are woken with their [Waker] when the token is
+//! released; there are no fairness guarantees, and a steady stream of readers can keep a writer
+//! waiting.
+
+use std::cell::UnsafeCell;
+use std::fmt::Debug;
+use std::future::Future;
+use std::marker::PhantomData;
+use std::ops::{Deref, DerefMut};
+use std::pin::Pin;
+use std::sync::{Mutex, MutexGuard};
+use std::task::{Context, Poll, Waker};
+
+use crate::tokens::TokenWith;
+
+#[derive(Default)]
+struct State {
+    readers: usize,
+    writer: bool,
+    wakers: Vec<Waker>,
+}
+
+// The bookkeeping shared by both locks. The mutex only ever acquires it exclusively.
+#[derive(Default)]
+struct RawLock {
+    state: Mutex<State>,
+}
+
+impl RawLock {
+    const fn new() -> Self {
+        Self {
+            state: Mutex::new(State { readers: 0, writer: false, wakers: Vec::new() }),
+        }
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        // The state is only changed in single steps, so a panic elsewhere can't leave it
+        // inconsistent
+        self.state.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    fn acquire(state: &mut State, exclusive: bool) -> bool {
+        if state.writer || (exclusive && state.readers > 0) {
+            return false;
+        }
+
+        if exclusive {
+            state.writer = true;
+        } else {
+            state.readers += 1;
+        }
+        true
+    }
+
+    fn try_acquire(&self, exclusive: bool) -> bool {
+        Self::acquire(&mut self.state(), exclusive)
+    }
+
+    fn poll_acquire(&self, exclusive: bool, cx: &mut Context<'_>) -> Poll<()> {
+        let mut state = self.state();
+        // Checked while holding the state lock, so a release can't slip in between the check and
+        // registering the waker
+        if Self::acquire(&mut state, exclusive) {
+            return Poll::Ready(());
+        }
+
+        if !state.wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            state.wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+
+    fn release(&self, exclusive: bool) {
+        let wakers = {
+            let mut state = self.state();
+            if exclusive {
+                state.writer = false;
+            } else {
+                state.readers -= 1;
+            }
+
+            if state.readers == 0 {
+                std::mem::take(&mut state.wakers)
+            } else {
+                Vec::new()
+            }
+        };
+
+        for waker in wakers {
+            waker.wake();
+        }
+    }
+}
+
+/// A token behind an async mutex. The guard derefs to the token, so it can be passed directly to
+/// [Cell::borrow](crate::Cell::borrow) and [Cell::borrow_mut](crate::Cell::borrow_mut).
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, lock::async_lock::AsyncTokenMutex};
+/// let (token, _) = first().unwrap().token();
+/// let token = AsyncTokenMutex::new(token);
+/// let log = Cell::new(Vec::new());
+///
+/// // Any executor works; this one happens to be tokio's
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// let held = token.try_lock().unwrap();
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         let mut held = held;
+///         log.borrow_mut(&mut held).push("first");
+///     });
+///     runtime.block_on(async {
+///         log.borrow_mut(&mut *token.lock().await).push("second");
+///     });
+/// });
+///
+/// assert_eq!(log.borrow(&token.try_lock().unwrap()), &["first", "second"]);
+/// ```
+pub struct AsyncTokenMutex<U, const ID: usize> {
+    raw: RawLock,
+    token: UnsafeCell<TokenWith<U, ID>>,
+}
+
+unsafe impl<U: Send, const ID: usize> Send for AsyncTokenMutex<U, ID> {}
+unsafe impl<U: Send, const ID: usize> Sync for AsyncTokenMutex<U, ID> {}
+
+impl<U, const ID: usize> AsyncTokenMutex<U, ID> {
+    pub const fn new(token: TokenWith<U, ID>) -> Self {
+        Self {
+            raw: RawLock::new(),
+            token: UnsafeCell::new(token),
+        }
+    }
+
+    /// Waits until the token is available.
+    pub fn lock(&self) -> Lock<'_, U, ID> {
+        Lock { mutex: self }
+    }
+
+    pub fn try_lock(&self) -> Option<AsyncTokenMutexGuard<'_, U, ID>> {
+        self.raw.try_acquire(true).then_some(AsyncTokenMutexGuard { mutex: self, _token: PhantomData })
+    }
+
+    pub fn into_inner(self) -> TokenWith<U, ID> {
+        self.token.into_inner()
+    }
+}
+
+/// Future returned by [AsyncTokenMutex::lock].
+pub struct Lock<'a, U, const ID: usize> {
+    mutex: &'a AsyncTokenMutex<U, ID>,
+}
+
+impl<'a, U, const ID: usize> Future for Lock<'a, U, ID> {
+    type Output = AsyncTokenMutexGuard<'a, U, ID>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let mutex = self.mutex;
+        mutex.raw.poll_acquire(true, cx).map(|()| AsyncTokenMutexGuard { mutex, _token: PhantomData })
+    }
+}
+
+/// Exclusive access to an [AsyncTokenMutex]'s token. Like [std::sync::MutexGuard], it's only
+/// `Sync` if the payload is, since sharing the guard shares the token.
+///
+/// ```compile_fail
+/// # use frankencell::{first, lock::async_lock::AsyncTokenMutex};
+/// fn assert_sync<T: Sync>(_: &T) {}
+///
+/// let (_, next) = first().unwrap().token();
+/// let (token, _) = next.token_with(std::cell::Cell::new(0u32));
+/// let token = AsyncTokenMutex::new(token);
+/// assert_sync(&token.try_lock().unwrap());
+/// ```
+pub struct AsyncTokenMutexGuard<'a, U, const ID: usize> {
+    mutex: &'a AsyncTokenMutex<U, ID>,
+    // `&AsyncTokenMutex` is `Sync` whenever `U: Send`, which is too weak for a guard
+    _token: PhantomData<&'a mut TokenWith<U, ID>>,
+}
+
+impl<U, const ID: usize> Drop for AsyncTokenMutexGuard<'_, U, ID> {
+    fn drop(&mut self) {
+        self.mutex.raw.release(true);
+    }
+}
+
+impl<U, const ID: usize> Deref for AsyncTokenMutexGuard<'_, U, ID> {
+    type Target = TokenWith<U, ID>;
+
+    fn deref(&self) -> &TokenWith<U, ID> {
+        // Safety: the guard holds the lock exclusively
+        unsafe {&*self.mutex.token.get()}
+    }
+}
+
+impl<U, const ID: usize> DerefMut for AsyncTokenMutexGuard<'_, U, ID> {
+    fn deref_mut(&mut self) -> &mut TokenWith<U, ID> {
+        unsafe {&mut *self.mutex.token.get()}
+    }
+}
+
+impl<U: Debug, const ID: usize> Debug for AsyncTokenMutexGuard<'_, U, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+/// A token behind an async reader-writer lock, so that several tasks can read the family's cells
+/// at once. Read guards deref to `&TokenWith` and write guards to `&mut TokenWith`.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, lock::async_lock::AsyncTokenRwLock};
+/// let (token, _) = first().unwrap().token();
+/// let token = AsyncTokenRwLock::new(token);
+/// let config = Cell::new(String::from("v1"));
+///
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+/// runtime.block_on(async {
+///     let (a, b) = (token.read().await, token.read().await);
+///     assert_eq!(config.borrow(&a), config.borrow(&b));
+///     assert!(token.try_write().is_none());
+///     drop((a, b));
+///
+///     config.borrow_mut(&mut *token.write().await).push('!');
+/// });
+///
+/// assert_eq!(config.borrow(&token.try_read().unwrap()), "v1!");
+/// ```
+pub struct AsyncTokenRwLock<U, const ID: usize> {
+    raw: RawLock,
+    token: UnsafeCell<TokenWith<U, ID>>,
+}
+
+unsafe impl<U: Send, const ID: usize> Send for AsyncTokenRwLock<U, ID> {}
+unsafe impl<U: Send + Sync, const ID: usize> Sync for AsyncTokenRwLock<U, ID> {}
+
+impl<U, const ID: usize> AsyncTokenRwLock<U, ID> {
+    pub const fn new(token: TokenWith<U, ID>) -> Self {
+        Self {
+            raw: RawLock::new(),
+            token: UnsafeCell::new(token),
+        }
+    }
+
+    /// Waits until no write guard exists.
+    pub fn read(&self) -> Read<'_, U, ID> {
+        Read { lock: self }
+    }
+
+    pub fn try_read(&self) -> Option<AsyncTokenReadGuard<'_, U, ID>> {
+        self.raw.try_acquire(false).then_some(AsyncTokenReadGuard { lock: self })
+    }
+
+    /// Waits until no other guard exists.
+    pub fn write(&self) -> Write<'_, U, ID> {
+        Write { lock: self }
+    }
+
+    pub fn try_write(&self) -> Option<AsyncTokenWriteGuard<'_, U, ID>> {
+        self.raw.try_acquire(true).then_some(AsyncTokenWriteGuard { lock: self })
+    }
+
+    pub fn into_inner(self) -> TokenWith<U, ID> {
+        self.token.into_inner()
+    }
+}
+
+/// Future returned by [AsyncTokenRwLock::read].
+pub struct Read<'a, U, const ID: usize> {
+    lock: &'a AsyncTokenRwLock<U, ID>,
+}
+
+impl<'a, U, const ID: usize> Future for Read<'a, U, ID> {
+    type Output = AsyncTokenReadGuard<'a, U, ID>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let lock = self.lock;
+        lock.raw.poll_acquire(false, cx).map(|()| AsyncTokenReadGuard { lock })
+    }
+}
+
+/// Future returned by [AsyncTokenRwLock::write].
+pub struct Write<'a, U, const ID: usize> {
+    lock: &'a AsyncTokenRwLock<U, ID>,
+}
+
+impl<'a, U, const ID: usize> Future for Write<'a, U, ID> {
+    type Output = AsyncTokenWriteGuard<'a, U, ID>;
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        let lock = self.lock;
+        lock.raw.poll_acquire(true, cx).map(|()| AsyncTokenWriteGuard { lock })
+    }
+}
+
+/// Shared access to an [AsyncTokenRwLock]'s token.
+pub struct AsyncTokenReadGuard<'a, U, const ID: usize> {
+    lock: &'a AsyncTokenRwLock<U, ID>,
+}
+
+/// Exclusive access to an [AsyncTokenRwLock]'s token.
+pub struct AsyncTokenWriteGuard<'a, U, const ID: usize> {
+    lock: &'a AsyncTokenRwLock<U, ID>,
+}
+
+impl<U, const ID: usize> Drop for AsyncTokenReadGuard<'_, U, ID> {
+    fn drop(&mut self) {
+        self.lock.raw.release(false);
+    }
+}
+
+impl<U, const ID: usize> Drop for AsyncTokenWriteGuard<'_, U, ID> {
+    fn drop(&mut self) {
+        self.lock.raw.release(true);
+    }
+}
+
+impl<U, const ID: usize> Deref for AsyncTokenReadGuard<'_, U, ID> {
+    type Target = TokenWith<U, ID>;
+
+    fn deref(&self) -> &TokenWith<U, ID> {
+        // Safety: no write guard exists while a read guard does
+        unsafe {&*self.lock.token.get()}
+    }
+}
+
+impl<U, const ID: usize> Deref for AsyncTokenWriteGuard<'_, U, ID> {
+    type Target = TokenWith<U, ID>;
+
+    fn deref(&self) -> &TokenWith<U, ID> {
+        // Safety: the guard holds the lock exclusively
+        unsafe {&*self.lock.token.get()}
+    }
+}
+
+impl<U, const ID: usize> DerefMut for AsyncTokenWriteGuard<'_, U, ID> {
+    fn deref_mut(&mut self) -> &mut TokenWith<U, ID> {
+        unsafe {&mut *self.lock.token.get()}
+    }
+}
+
+impl<U: Debug, const ID: usize> Debug for AsyncTokenReadGuard<'_, U, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
+
+impl<U: Debug, const ID: usize> Debug for AsyncTokenWriteGuard<'_, U, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        (**self).fmt(f)
+    }
+}
@@ -0,0 +1,110 @@
+//! Lock-order tracking for the `deadlock-detection` feature.
+//!
+//! Every time a thread blocks on a family's token lock while holding other families' token locks,
+//! the order is recorded as an edge in a graph shared by all threads. If the new edge would close
+//! a cycle, some pair of threads could each hold one of the locks while waiting for the other, so
+//! the acquisition panics instead, whether or not the threads ever actually collide.
+//!
+//! Tracking is only enabled with both the `deadlock-detection` feature and `debug_assertions`, so
+//! release builds pay nothing. Guards mapped down to a single cell with the `parking_lot`
+//! adapters' `map` are no longer tracked.
+
+#[cfg(all(feature = "deadlock-detection", debug_assertions))]
+mod imp {
+    use std::cell::RefCell;
+    use std::collections::{BTreeMap, BTreeSet};
+    use std::sync::{Mutex, MutexGuard};
+
+    // `from -> to` means `to` was locked while holding `from`
+    static EDGES: Mutex<BTreeMap<usize, BTreeSet<usize>>> = Mutex::new(BTreeMap::new());
+
+    thread_local! {
+        // Families whose token lock this thread holds, in the order they were acquired
+        static HELD: RefCell<Vec<usize>> = const { RefCell::new(Vec::new()) };
+    }
+
+    fn edges() -> MutexGuard<'static, BTreeMap<usize, BTreeSet<usize>>> {
+        // Edges are only ever inserted one at a time, so a panic elsewhere can't leave the graph
+        // inconsistent
+        EDGES.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    // A path of families from `from` to `to`, if one has been recorded
+    fn path(edges: &BTreeMap<usize, BTreeSet<usize>>, from: usize, to: usize) -> Option<Vec<usize>> {
+        let mut stack = vec![vec![from]];
+        let mut seen = BTreeSet::new();
+
+        while let Some(path) = stack.pop() {
+            let last = *path.last().unwrap();
+            if last == to {
+                return Some(path);
+            }
+            if !seen.insert(last) {
+                continue;
+            }
+            for &next in edges.get(&last).into_iter().flatten() {
+                let mut path = path.clone();
+                path.push(next);
+                stack.push(path);
+            }
+        }
+        None
+    }
+
+    #[track_caller]
+    pub(crate) fn check(id: usize) {
+        let held = HELD.with(|held| held.borrow().clone());
+        let mut edges = edges();
+
+        for from in held.into_iter().filter(|&from| from != id) {
+            if let Some(cycle) = path(&edges, id, from) {
+                drop(edges);
+                let cycle: Vec<_> = cycle.iter().map(usize::to_string).collect();
+                panic!(
+                    "possible deadlock: locking family {id}'s token while holding family {from}'s, \
+                    but they were earlier locked in the order {} -> {id}",
+                    cycle.join(" -> "),
+                );
+            }
+            edges.entry(from).or_default().insert(id);
+        }
+    }
+
+    /// Marks a family's token lock as held by this thread until dropped.
+    pub(crate) struct Held(usize);
+
+    impl Held {
+        pub(crate) fn new(id: usize) -> Self {
+            HELD.with(|held| held.borrow_mut().push(id));
+            Self(id)
+        }
+    }
+
+    impl Drop for Held {
+        fn drop(&mut self) {
+            HELD.with(|held| {
+                let mut held = held.borrow_mut();
+                if let Some(i) = held.iter().rposition(|&id| id == self.0) {
+                    held.remove(i);
+                }
+            });
+        }
+    }
+}
+
+#[cfg(not(all(feature = "deadlock-detection", debug_assertions)))]
+mod imp {
+    #[inline(always)]
+    pub(crate) fn check(_: usize) {}
+
+    pub(crate) struct Held;
+
+    impl Held {
+        #[inline(always)]
+        pub(crate) fn new(_: usize) -> Self {
+            Self
+        }
+    }
+}
+
+pub(crate) use imp::*;
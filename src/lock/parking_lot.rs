@@ -0,0 +1,143 @@
+//! A [parking_lot](::parking_lot)-backed reader-writer lock for tokens. Unlike
+//! [super::TokenRwLock] it never poisons, and its guards can be mapped down to a single cell's
+//! contents so the borrow can be handed out on its own.
+
+use std::ops::{Deref, DerefMut};
+use std::time::Duration;
+
+use ::parking_lot::{MappedRwLockReadGuard, MappedRwLockWriteGuard, RwLock, RwLockReadGuard, RwLockWriteGuard};
+
+use crate::cells::Cell;
+use crate::lock::{order, LockTimeout};
+use crate::tokens::TokenWith;
+
+/// A token behind a [parking_lot::RwLock](::parking_lot::RwLock). Read guards deref to
+/// `&TokenWith` and write guards to `&mut TokenWith`, so they can be passed directly to
+/// [Cell::borrow] and [Cell::borrow_mut].
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, lock::parking_lot::{TokenRwLock, TokenReadGuard, TokenWriteGuard}};
+/// let (token, _) = first().unwrap().token();
+/// let token = TokenRwLock::new(token);
+/// let names = Cell::new(vec!["a"]);
+///
+/// TokenWriteGuard::map(token.write(), &names).push("b");
+///
+/// let names: parking_lot::MappedRwLockReadGuard<'_, Vec<&str>> = TokenReadGuard::map(token.read(), &names);
+/// assert_eq!(*names, ["a", "b"]);
+/// ```
+pub struct TokenRwLock<U, const ID: usize> {
+    inner: RwLock<TokenWith<U, ID>>,
+}
+
+/// Shared access to a [TokenRwLock]'s token.
+pub struct TokenReadGuard<'a, U, const ID: usize> {
+    guard: RwLockReadGuard<'a, TokenWith<U, ID>>,
+    _held: order::Held,
+}
+
+/// Exclusive access to a [TokenRwLock]'s token.
+pub struct TokenWriteGuard<'a, U, const ID: usize> {
+    guard: RwLockWriteGuard<'a, TokenWith<U, ID>>,
+    _held: order::Held,
+}
+
+impl<U, const ID: usize> TokenRwLock<U, ID> {
+    pub const fn new(token: TokenWith<U, ID>) -> Self {
+        Self {
+            inner: RwLock::new(token),
+        }
+    }
+
+    pub fn read(&self) -> TokenReadGuard<'_, U, ID> {
+        order::check(ID);
+        TokenReadGuard { guard: self.inner.read(), _held: order::Held::new(ID) }
+    }
+
+    pub fn try_read(&self) -> Option<TokenReadGuard<'_, U, ID>> {
+        self.inner.try_read().map(|guard| TokenReadGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    pub fn write(&self) -> TokenWriteGuard<'_, U, ID> {
+        order::check(ID);
+        TokenWriteGuard { guard: self.inner.write(), _held: order::Held::new(ID) }
+    }
+
+    pub fn try_write(&self) -> Option<TokenWriteGuard<'_, U, ID>> {
+        self.inner.try_write().map(|guard| TokenWriteGuard { guard, _held: order::Held::new(ID) })
+    }
+
+    /// Like [Self::read], but gives up after `timeout`.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, lock::parking_lot::TokenRwLock};
+    /// # use std::time::Duration;
+    /// let (token, _) = first().unwrap().token();
+    /// let token = TokenRwLock::new(token);
+    ///
+    /// let writer = token.write();
+    /// let err = token.try_read_for(Duration::from_millis(5)).err().unwrap();
+    /// assert_eq!(err.timeout(), Duration::from_millis(5));
+    ///
+    /// drop(writer);
+    /// assert!(token.try_write_for(Duration::from_millis(5)).is_ok());
+    /// ```
+    pub fn try_read_for(&self, timeout: Duration) -> Result<TokenReadGuard<'_, U, ID>, LockTimeout> {
+        order::check(ID);
+        self.inner.try_read_for(timeout).map(|guard| TokenReadGuard { guard, _held: order::Held::new(ID) }).ok_or(LockTimeout::new(timeout))
+    }
+
+    /// Like [Self::write], but gives up after `timeout`.
+    pub fn try_write_for(&self, timeout: Duration) -> Result<TokenWriteGuard<'_, U, ID>, LockTimeout> {
+        order::check(ID);
+        self.inner.try_write_for(timeout).map(|guard| TokenWriteGuard { guard, _held: order::Held::new(ID) }).ok_or(LockTimeout::new(timeout))
+    }
+
+    pub fn into_inner(self) -> TokenWith<U, ID> {
+        self.inner.into_inner()
+    }
+}
+
+impl<'a, U, const ID: usize> TokenReadGuard<'a, U, ID> {
+    /// Turns this guard into one that only gives access to `cell`'s contents. This is an
+    /// associated function so it doesn't shadow methods on the token.
+    pub fn map<T>(s: Self, cell: &'a Cell<T, ID>) -> MappedRwLockReadGuard<'a, T> {
+        // Safety: the read lock is held until the mapped guard is dropped, so no `&mut Token`
+        // (and therefore no `&mut T`) can exist in the meantime, and `cell` outlives the guard.
+        RwLockReadGuard::map(s.guard, |_| unsafe {&*cell.as_ptr()})
+    }
+}
+
+impl<'a, U, const ID: usize> TokenWriteGuard<'a, U, ID> {
+    /// Turns this guard into one that only gives access to `cell`'s contents. This is an
+    /// associated function so it doesn't shadow methods on the token.
+    pub fn map<T>(s: Self, cell: &'a Cell<T, ID>) -> MappedRwLockWriteGuard<'a, T> {
+        // Safety: the write lock is held until the mapped guard is dropped, and the token can't be
+        // reached through the mapped guard, so this is the only borrow of `cell`.
+        RwLockWriteGuard::map(s.guard, |_| unsafe {&mut *cell.as_ptr().cast_mut()})
+    }
+}
+
+impl<U, const ID: usize> Deref for TokenReadGuard<'_, U, ID> {
+    type Target = TokenWith<U, ID>;
+
+    fn deref(&self) -> &TokenWith<U, ID> {
+        &self.guard
+    }
+}
+
+impl<U, const ID: usize> Deref for TokenWriteGuard<'_, U, ID> {
+    type Target = TokenWith<U, ID>;
+
+    fn deref(&self) -> &TokenWith<U, ID> {
+        &self.guard
+    }
+}
+
+impl<U, const ID: usize> DerefMut for TokenWriteGuard<'_, U, ID> {
+    fn deref_mut(&mut self) -> &mut TokenWith<U, ID> {
+        &mut self.guard
+    }
+}
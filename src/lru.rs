@@ -0,0 +1,210 @@
+use std::borrow::Borrow;
+use std::collections::HashMap;
+use std::hash::Hash;
+
+use crate::arena::{Arena, Index, WeakIndex};
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+type Link<K, V, const ID: usize> = Option<WeakIndex<Entry<K, V, ID>, ID>>;
+
+struct Entry<K, V, const ID: usize> {
+    key: K,
+    value: V,
+    prev: Link<K, V, ID>,
+    next: Link<K, V, ID>,
+}
+
+/// A fixed-capacity cache that evicts its least recently used entry. The recency list is linked
+/// through an [Arena] of cells, so [Self::get] can move an entry to the front with a `&mut Token`
+/// while the cache itself stays shared; only adding and removing entries needs `&mut self`.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, lru::LruCache};
+/// let (mut token, _) = first().unwrap().token();
+/// let mut cache = LruCache::new(2);
+///
+/// cache.insert("a", 1, &mut token);
+/// cache.insert("b", 2, &mut token);
+///
+/// // Reading "a" makes "b" the least recently used entry
+/// let shared = &cache;
+/// assert_eq!(shared.get("a", &mut token), Some(&1));
+///
+/// assert_eq!(cache.insert("c", 3, &mut token), Some(("b", 2)));
+/// assert_eq!(cache.peek("b", &token), None);
+/// assert_eq!(cache.len(), 2);
+/// ```
+pub struct LruCache<K, V, const ID: usize> {
+    map: HashMap<K, Index<Entry<K, V, ID>, ID>>,
+    entries: Arena<Entry<K, V, ID>, ID>,
+    // The most and least recently used entries
+    ends: Cell<(Link<K, V, ID>, Link<K, V, ID>), ID>,
+    capacity: usize,
+}
+
+impl<K: Hash + Eq + Clone, V, const ID: usize> LruCache<K, V, ID> {
+    /// # Panics
+    /// Panics if `capacity` is 0.
+    pub fn new(capacity: usize) -> Self {
+        assert!(capacity > 0, "LruCache must have a non-zero capacity");
+
+        Self {
+            map: HashMap::with_capacity(capacity),
+            entries: Arena::with_capacity(capacity),
+            ends: Cell::new((None, None)),
+            capacity,
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.map.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.map.is_empty()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn contains_key<Q: Hash + Eq + ?Sized>(&self, key: &Q) -> bool where K: Borrow<Q> {
+        self.map.contains_key(key)
+    }
+
+    fn entry<'a, U>(&'a self, link: WeakIndex<Entry<K, V, ID>, ID>, token: &'a TokenWith<U, ID>) -> &'a Entry<K, V, ID> {
+        self.entries.get(&self.entries.upgrade(link).unwrap(), token)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn entry_mut<'a, U>(&'a self, link: WeakIndex<Entry<K, V, ID>, ID>, token: &'a mut TokenWith<U, ID>) -> &'a mut Entry<K, V, ID> {
+        self.entries.get_mut(&self.entries.upgrade(link).unwrap(), token)
+    }
+
+    fn unlink<U>(&self, link: WeakIndex<Entry<K, V, ID>, ID>, token: &mut TokenWith<U, ID>) {
+        let entry = self.entry_mut(link, token);
+        let (prev, next) = (entry.prev.take(), entry.next.take());
+
+        match prev {
+            Some(prev) => self.entry_mut(prev, token).next = next,
+            None => self.ends.borrow_mut(token).0 = next,
+        }
+        match next {
+            Some(next) => self.entry_mut(next, token).prev = prev,
+            None => self.ends.borrow_mut(token).1 = prev,
+        }
+    }
+
+    fn push_front<U>(&self, link: WeakIndex<Entry<K, V, ID>, ID>, token: &mut TokenWith<U, ID>) {
+        let head = self.ends.borrow_mut(token).0.replace(link);
+        self.entry_mut(link, token).next = head;
+
+        match head {
+            Some(head) => self.entry_mut(head, token).prev = Some(link),
+            None => self.ends.borrow_mut(token).1 = Some(link),
+        }
+    }
+
+    fn touch<U>(&self, link: WeakIndex<Entry<K, V, ID>, ID>, token: &mut TokenWith<U, ID>) {
+        if self.ends.borrow(token).0 != Some(link) {
+            self.unlink(link, token);
+            self.push_front(link, token);
+        }
+    }
+
+    /// Looks up `key`, marking its entry as the most recently used one.
+    pub fn get<'a, U, Q>(&'a self, key: &Q, token: &'a mut TokenWith<U, ID>) -> Option<&'a V>
+        where K: Borrow<Q>,
+              Q: Hash + Eq + ?Sized,
+    {
+        let link = self.map.get(key)?.downgrade();
+        self.touch(link, token);
+        Some(&self.entry(link, token).value)
+    }
+
+    /// Looks up `key` mutably, marking its entry as the most recently used one.
+    #[allow(clippy::mut_from_ref)]
+    pub fn get_mut<'a, U, Q>(&'a self, key: &Q, token: &'a mut TokenWith<U, ID>) -> Option<&'a mut V>
+        where K: Borrow<Q>,
+              Q: Hash + Eq + ?Sized,
+    {
+        let link = self.map.get(key)?.downgrade();
+        self.touch(link, token);
+        Some(&mut self.entry_mut(link, token).value)
+    }
+
+    /// Looks up `key` without changing how recently it was used.
+    pub fn peek<'a, U, Q>(&'a self, key: &Q, token: &'a TokenWith<U, ID>) -> Option<&'a V>
+        where K: Borrow<Q>,
+              Q: Hash + Eq + ?Sized,
+    {
+        self.map.get(key).map(|index| &self.entries.get(index, token).value)
+    }
+
+    /// Inserts an entry as the most recently used one. Returns the entry that was pushed out:
+    /// the previous value for `key` if there was one, or else the least recently used entry if
+    /// the cache was full.
+    pub fn insert<U>(&mut self, key: K, value: V, token: &mut TokenWith<U, ID>) -> Option<(K, V)> {
+        if let Some(index) = self.map.get(&key) {
+            let link = index.downgrade();
+            self.touch(link, token);
+            let old = std::mem::replace(&mut self.entry_mut(link, token).value, value);
+            return Some((key, old));
+        }
+
+        let evicted = if self.map.len() == self.capacity {
+            self.pop_lru(token)
+        } else {
+            None
+        };
+
+        let index = self.entries.insert(Entry { key: key.clone(), value, prev: None, next: None });
+        self.push_front(index.downgrade(), token);
+        self.map.insert(key, index);
+
+        evicted
+    }
+
+    pub fn remove<U, Q>(&mut self, key: &Q, token: &mut TokenWith<U, ID>) -> Option<V>
+        where K: Borrow<Q>,
+              Q: Hash + Eq + ?Sized,
+    {
+        let index = self.map.remove(key)?;
+        self.unlink(index.downgrade(), token);
+        Some(self.entries.remove(index).value)
+    }
+
+    /// Removes and returns the least recently used entry.
+    pub fn pop_lru<U>(&mut self, token: &mut TokenWith<U, ID>) -> Option<(K, V)> {
+        let link = self.ends.borrow(token).1?;
+        self.unlink(link, token);
+
+        let key = &self.entries.get(&self.entries.upgrade(link).unwrap(), token).key;
+        let index = self.map.remove(key).unwrap();
+        let entry = self.entries.remove(index);
+        Some((entry.key, entry.value))
+    }
+}
+
+#[test]
+fn lru_order_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let mut cache = LruCache::new(3);
+
+    for i in 0..3 {
+        cache.insert(i, i * 10, &mut token);
+    }
+    *cache.get_mut(&0, &mut token).unwrap() += 1;
+    assert_eq!(cache.remove(&1, &mut token), Some(10));
+    cache.insert(3, 30, &mut token);
+
+    let mut order = Vec::new();
+    while let Some(entry) = cache.pop_lru(&mut token) {
+        order.push(entry);
+    }
+    assert_eq!(order, [(2, 20), (0, 1), (3, 30)]);
+}
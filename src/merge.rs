@@ -0,0 +1,225 @@
+//! Merging several token families into one owner, for programs that split their state across more
+//! families than they turned out to need.
+
+use crate::cells::Cell;
+use crate::tokens::{Token, TokenWith};
+
+/// Marks a family found in the first half of a [MergedToken].
+pub struct First<W>(W);
+
+/// Marks a family found in the second half of a [MergedToken].
+pub struct Second<W>(W);
+
+/// Marks a family owned by a plain [TokenWith].
+pub struct Here;
+
+/// Anything that owns family `ID`'s token: the token itself, or a [MergedToken] containing it.
+/// `W` records where the token is found, and is always inferred, so that a merged token can own
+/// several families without their impls overlapping.
+pub trait Owns<const ID: usize, W> {
+    type Payload;
+
+    fn token(&self) -> &TokenWith<Self::Payload, ID>;
+
+    fn token_mut(&mut self) -> &mut TokenWith<Self::Payload, ID>;
+}
+
+impl<U, const ID: usize> Owns<ID, Here> for TokenWith<U, ID> {
+    type Payload = U;
+
+    fn token(&self) -> &TokenWith<U, ID> {
+        self
+    }
+
+    fn token_mut(&mut self) -> &mut TokenWith<U, ID> {
+        self
+    }
+}
+
+/// Two tokens merged into one owner, which can borrow cells from either family (or from any family
+/// owned by either token, if they are merged tokens themselves). Both tokens are moved in, so
+/// there's only ever one way to reach each family.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, merge::MergedToken};
+/// let (a, next) = first().unwrap().token();
+/// let (b, next) = next.token();
+/// let (c, _) = next.token();
+///
+/// // A merged token can't tell which family a new cell is meant for
+/// let names: Cell<_, 0> = Cell::new(vec!["ferris"]);
+/// let count: Cell<_, 1> = Cell::new(0);
+/// let flag: Cell<_, 2> = Cell::new(false);
+///
+/// let mut token = MergedToken::new(MergedToken::new(a, b), c);
+///
+/// let len = names.borrow_from(&token).len();
+/// *count.borrow_mut_from(&mut token) += len;
+/// *flag.borrow_mut_from(&mut token) = true;
+///
+/// let (ab, c) = token.split();
+/// let (a, _) = ab.split();
+/// assert_eq!(names.borrow(&a).len(), 1);
+/// assert!(flag.borrow(&c));
+/// ```
+pub struct MergedToken<X, Y> {
+    first: X,
+    second: Y,
+}
+
+impl<X, Y> MergedToken<X, Y> {
+    pub fn new(first: X, second: Y) -> Self {
+        Self { first, second }
+    }
+
+    /// Gives back the tokens that were merged.
+    pub fn split(self) -> (X, Y) {
+        (self.first, self.second)
+    }
+}
+
+impl<X: Owns<ID, W>, Y, W, const ID: usize> Owns<ID, First<W>> for MergedToken<X, Y> {
+    type Payload = X::Payload;
+
+    fn token(&self) -> &TokenWith<X::Payload, ID> {
+        self.first.token()
+    }
+
+    fn token_mut(&mut self) -> &mut TokenWith<X::Payload, ID> {
+        self.first.token_mut()
+    }
+}
+
+impl<X, Y: Owns<ID, W>, W, const ID: usize> Owns<ID, Second<W>> for MergedToken<X, Y> {
+    type Payload = Y::Payload;
+
+    fn token(&self) -> &TokenWith<Y::Payload, ID> {
+        self.second.token()
+    }
+
+    fn token_mut(&mut self) -> &mut TokenWith<Y::Payload, ID> {
+        self.second.token_mut()
+    }
+}
+
+impl<T, const ID: usize> Cell<T, ID> {
+    /// Like [Cell::borrow], but accepts anything that [Owns] this cell's family, such as a
+    /// [MergedToken].
+    pub fn borrow_from<'a, W, O: Owns<ID, W>>(&'a self, owner: &'a O) -> &'a T where O::Payload: 'a {
+        self.borrow(owner.token())
+    }
+
+    /// Like [Cell::borrow_mut], but accepts anything that [Owns] this cell's family, such as a
+    /// [MergedToken].
+    #[allow(clippy::mut_from_ref)]
+    pub fn borrow_mut_from<'a, W, O: Owns<ID, W>>(&'a self, owner: &'a mut O) -> &'a mut T where O::Payload: 'a {
+        self.borrow_mut(owner.token_mut())
+    }
+}
+
+/// Anything that may own family `ID`'s token, only known at runtime: an [EitherToken], or anything
+/// that always [Owns] it.
+pub trait MaybeOwns<const ID: usize, W> {
+    type Payload;
+
+    fn try_token(&self) -> Option<&TokenWith<Self::Payload, ID>>;
+
+    fn try_token_mut(&mut self) -> Option<&mut TokenWith<Self::Payload, ID>>;
+}
+
+impl<O: Owns<ID, W>, W, const ID: usize> MaybeOwns<ID, W> for O {
+    type Payload = O::Payload;
+
+    fn try_token(&self) -> Option<&TokenWith<O::Payload, ID>> {
+        Some(self.token())
+    }
+
+    fn try_token_mut(&mut self) -> Option<&mut TokenWith<O::Payload, ID>> {
+        Some(self.token_mut())
+    }
+}
+
+/// The token of one of two families, for code in the middle of migrating data from family `L` to
+/// family `R` that has to work with cells of either. Borrowing a cell of the family it doesn't
+/// hold returns `None`.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, merge::{EitherToken, MaybeOwns}};
+/// let (old, next) = first().unwrap().token();
+/// let (mut new, _) = next.token();
+///
+/// let legacy: Cell<_, 0> = Cell::new(String::from("legacy"));
+/// let migrated: Cell<_, 1> = Cell::new(String::from("migrated"));
+///
+/// // Written once, for whichever family a caller has
+/// fn shout<W, const ID: usize>(name: &Cell<String, ID>, token: &mut impl MaybeOwns<ID, W>) -> bool {
+///     name.try_borrow_mut_from(token).map(|name| name.make_ascii_uppercase()).is_some()
+/// }
+///
+/// let mut token = EitherToken::<0, 1>::Left(old);
+/// assert!(shout(&legacy, &mut token));
+/// assert!(!shout(&migrated, &mut token));
+///
+/// let EitherToken::Left(old) = token else { unreachable!() };
+/// assert_eq!(legacy.borrow(&old), "LEGACY");
+///
+/// assert!(shout(&migrated, &mut new));
+/// ```
+#[derive(Debug)]
+pub enum EitherToken<const L: usize, const R: usize> {
+    Left(Token<L>),
+    Right(Token<R>),
+}
+
+impl<const L: usize, const R: usize> MaybeOwns<L, First<Here>> for EitherToken<L, R> {
+    type Payload = ();
+
+    fn try_token(&self) -> Option<&Token<L>> {
+        match self {
+            Self::Left(token) => Some(token),
+            Self::Right(_) => None,
+        }
+    }
+
+    fn try_token_mut(&mut self) -> Option<&mut Token<L>> {
+        match self {
+            Self::Left(token) => Some(token),
+            Self::Right(_) => None,
+        }
+    }
+}
+
+impl<const L: usize, const R: usize> MaybeOwns<R, Second<Here>> for EitherToken<L, R> {
+    type Payload = ();
+
+    fn try_token(&self) -> Option<&Token<R>> {
+        match self {
+            Self::Left(_) => None,
+            Self::Right(token) => Some(token),
+        }
+    }
+
+    fn try_token_mut(&mut self) -> Option<&mut Token<R>> {
+        match self {
+            Self::Left(_) => None,
+            Self::Right(token) => Some(token),
+        }
+    }
+}
+
+impl<T, const ID: usize> Cell<T, ID> {
+    /// Like [Cell::borrow_from], but accepts anything that [MaybeOwns] this cell's family, such as
+    /// an [EitherToken], returning `None` if it doesn't hold the family's token.
+    pub fn try_borrow_from<'a, W, O: MaybeOwns<ID, W>>(&'a self, owner: &'a O) -> Option<&'a T> where O::Payload: 'a {
+        Some(self.borrow(owner.try_token()?))
+    }
+
+    /// Like [Cell::borrow_mut_from], but accepts anything that [MaybeOwns] this cell's family,
+    /// such as an [EitherToken], returning `None` if it doesn't hold the family's token.
+    #[allow(clippy::mut_from_ref)]
+    pub fn try_borrow_mut_from<'a, W, O: MaybeOwns<ID, W>>(&'a self, owner: &'a mut O) -> Option<&'a mut T> where O::Payload: 'a {
+        Some(self.borrow_mut(owner.try_token_mut()?))
+    }
+}
@@ -0,0 +1,107 @@
+//! Interop with [ndarray](::ndarray): arrays of cells can be viewed as plain arrays given the
+//! family's token, and a mutable plain array view can be turned into a view of cells.
+
+use ::ndarray::{ArrayBase, ArrayView, ArrayViewMut, Axis, DataMut, Dimension, NdIndex, ShapeBuilder};
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// Token-gated access to an ndarray of cells.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, ndarray::CellArrayExt};
+/// use ndarray::{array, s, Array2};
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let grid: Array2<Cell<i32, 0>> = array![[1, 2], [3, 4]].map(|&x| Cell::new(x));
+///
+/// grid.view_mut_with(&mut token).slice_mut(s![.., 1]).fill(0);
+///
+/// assert_eq!(grid.get_with([1, 0], &token), Some(&3));
+/// assert_eq!(grid.view_with(&token).sum(), 4);
+/// ```
+pub trait CellArrayExt<T, D: Dimension, const ID: usize> {
+    /// Borrows every element at once.
+    fn view_with<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> ArrayView<'a, T, D>;
+
+    /// Mutably borrows every element at once.
+    fn view_mut_with<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> ArrayViewMut<'a, T, D>;
+
+    /// Borrows the element at `index`, or returns `None` if it is out of bounds.
+    fn get_with<'a, U, I: NdIndex<D>>(&'a self, index: I, token: &'a TokenWith<U, ID>) -> Option<&'a T>;
+}
+
+impl<T, S, D, const ID: usize> CellArrayExt<T, D, ID> for ArrayBase<S, D>
+    where S: DataMut<Elem = Cell<T, ID>>,
+          D: Dimension,
+{
+    fn view_with<'a, U>(&'a self, _: &'a TokenWith<U, ID>) -> ArrayView<'a, T, D> {
+        // Safety: `Cell` is `repr(transparent)`, and the token is borrowed for as long as the view
+        unsafe {self.raw_view().cast::<T>().deref_into_view()}
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn view_mut_with<'a, U>(&'a self, _: &'a mut TokenWith<U, ID>) -> ArrayViewMut<'a, T, D> {
+        // Mutable views can only be built from non-negative strides, so flip any reversed axes
+        // first and flip them back afterwards
+        let mut raw = self.raw_view().cast::<T>();
+        let reversed: Vec<_> = (0..raw.ndim()).map(Axis).filter(|&axis| raw.stride_of(axis) < 0).collect();
+        for &axis in &reversed {
+            raw.invert_axis(axis);
+        }
+
+        let mut strides = raw.raw_dim();
+        for (stride, &s) in strides.slice_mut().iter_mut().zip(raw.strides()) {
+            *stride = s as usize;
+        }
+
+        // Safety: as above, with the token mutably borrowed. `DataMut` arrays never have two
+        // positions sharing an element, unlike broadcast views.
+        let mut view = unsafe {ArrayViewMut::from_shape_ptr(raw.raw_dim().strides(strides), raw.as_ptr() as *mut T)};
+        for axis in reversed {
+            view.invert_axis(axis);
+        }
+        view
+    }
+
+    fn get_with<'a, U, I: NdIndex<D>>(&'a self, index: I, token: &'a TokenWith<U, ID>) -> Option<&'a T> {
+        self.get(index).map(|cell| cell.borrow(token))
+    }
+}
+
+/// Views a mutably borrowed array as an array of cells, like [Cell::from_mut] does for single
+/// values.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, ndarray::from_view_mut};
+/// use ndarray::array;
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let mut values = array![1, 2, 3];
+///
+/// let cells = from_view_mut(values.view_mut());
+/// let (first, last) = (&cells[0], &cells[2]);
+/// *first.borrow_mut(&mut token) += *last.borrow(&token);
+///
+/// assert_eq!(values, array![4, 2, 3]);
+/// ```
+pub fn from_view_mut<T, D: Dimension, const ID: usize>(mut view: ArrayViewMut<'_, T, D>) -> ArrayView<'_, Cell<T, ID>, D> {
+    // Safety: the view is consumed, so the cells have exclusive access for its lifetime
+    unsafe {view.raw_view_mut().cast::<Cell<T, ID>>().deref_into_view()}
+}
+
+#[test]
+fn ndarray_reversed_axis_test() {
+    use ::ndarray::Array2;
+
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let mut cells = Array2::from_shape_fn((2, 3), |(i, j)| Cell::new(i * 3 + j));
+    cells.invert_axis(Axis(1));
+
+    cells.view_mut_with(&mut token)[[0, 0]] = 100;
+    assert_eq!(cells.view_with(&token).row(0).to_vec(), [100, 1, 0]);
+}
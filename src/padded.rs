@@ -0,0 +1,93 @@
+//! Over-aligned wrappers, so that cells read and written from different threads don't share a
+//! cache line. Without them, an array of small cells packs several to a line, and writing one
+//! slows down every core reading its neighbours.
+
+use std::fmt::Debug;
+use std::ops::{Deref, DerefMut};
+
+macro_rules! aligned {
+    ($(#[$attr:meta])* $name:ident) => {
+        $(#[$attr])*
+        #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+        pub struct $name<T>(pub T);
+
+        impl<T> $name<T> {
+            pub const fn new(t: T) -> Self {
+                Self(t)
+            }
+
+            pub fn into_inner(self) -> T {
+                self.0
+            }
+        }
+
+        impl<T> Deref for $name<T> {
+            type Target = T;
+
+            fn deref(&self) -> &T {
+                &self.0
+            }
+        }
+
+        impl<T> DerefMut for $name<T> {
+            fn deref_mut(&mut self) -> &mut T {
+                &mut self.0
+            }
+        }
+
+        impl<T> From<T> for $name<T> {
+            fn from(t: T) -> Self {
+                Self(t)
+            }
+        }
+
+        impl<T: Debug> Debug for $name<T> {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.debug_tuple(stringify!($name)).field(&self.0).finish()
+            }
+        }
+    };
+}
+
+aligned! {
+    /// Aligns `T` to the size of a cache line on the target, or to the pair of lines fetched
+    /// together on targets that prefetch adjacent lines (`x86_64`, `aarch64` and `powerpc64`).
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell, padded::CachePadded};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let counters: [CachePadded<Cell<u64, 0>>; 4] = Default::default();
+    ///
+    /// *counters[1].borrow_mut(&mut token) += 1;
+    /// assert_eq!(*counters[1].borrow(&token), 1);
+    /// assert!(std::mem::size_of_val(&counters[0]) >= 64);
+    /// ```
+    #[cfg_attr(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "powerpc64"), repr(align(128)))]
+    #[cfg_attr(not(any(target_arch = "x86_64", target_arch = "aarch64", target_arch = "powerpc64")), repr(align(64)))]
+    CachePadded
+}
+
+aligned! {
+    /// Aligns `T` to 16 bytes.
+    #[repr(align(16))]
+    Align16
+}
+
+aligned! {
+    /// Aligns `T` to 32 bytes.
+    #[repr(align(32))]
+    Align32
+}
+
+aligned! {
+    /// Aligns `T` to 64 bytes.
+    #[repr(align(64))]
+    Align64
+}
+
+aligned! {
+    /// Aligns `T` to 128 bytes.
+    #[repr(align(128))]
+    Align128
+}
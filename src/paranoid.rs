@@ -0,0 +1,105 @@
+//! Shadow borrow tracking for the `paranoid` feature.
+//!
+//! A borrow returned by [Cell::borrow](crate::Cell::borrow) or
+//! [Cell::borrow_mut](crate::Cell::borrow_mut) holds on to the token, so the next time a token of
+//! the same family is used, every earlier mutable borrow is guaranteed to be over. Recording the
+//! most recent mutable borrow of each family in a side table therefore lets [Cell::get] detect
+//! when it aliases a mutable borrow that may still be live. This is conservative: a borrow that has
+//! already been dropped still counts as live until the token is used again.
+//!
+//! [Cell::from_mut](crate::Cell::from_mut) isn't checked, since its `&mut T` already proves that
+//! no borrow of the value is live.
+//!
+//! [Cell::get]: crate::Cell::get
+//!
+//! Tracking is only enabled with both the `paranoid` feature and `debug_assertions`, so release
+//! builds pay nothing.
+
+#[cfg(all(feature = "paranoid", debug_assertions))]
+mod imp {
+    use std::collections::BTreeMap;
+    use std::sync::{Mutex, MutexGuard};
+
+    // The address of each family's most recent mutable borrow, keyed by family ID. Shared borrows
+    // only end it, so the table holds at most one entry per family.
+    static LIVE: Mutex<BTreeMap<usize, usize>> = Mutex::new(BTreeMap::new());
+
+    fn live() -> MutexGuard<'static, BTreeMap<usize, usize>> {
+        LIVE.lock().unwrap_or_else(|e| e.into_inner())
+    }
+
+    pub(crate) fn on_borrow(id: usize) {
+        live().remove(&id);
+    }
+
+    pub(crate) fn on_borrow_mut(id: usize, addr: usize) {
+        live().insert(id, addr);
+    }
+
+    #[track_caller]
+    pub(crate) fn check_shared(id: usize, addr: usize) {
+        if let Some(&a) = live().get(&id) {
+            assert!(a != addr, "Cell<_, {id}> accessed while it may be mutably borrowed");
+        }
+    }
+}
+
+#[cfg(not(all(feature = "paranoid", debug_assertions)))]
+mod imp {
+    #[inline(always)]
+    pub(crate) fn on_borrow(_: usize) {}
+
+    #[inline(always)]
+    pub(crate) fn on_borrow_mut(_: usize, _: usize) {}
+
+    #[inline(always)]
+    pub(crate) fn check_shared(_: usize, _: usize) {}
+}
+
+pub(crate) use imp::*;
+
+// Tests use their own families so other tests running concurrently can't affect the side table
+#[cfg(all(feature = "paranoid", debug_assertions))]
+#[test]
+#[should_panic(expected = "may be mutably borrowed")]
+fn paranoid_get_test() {
+    use crate::{Cell, TokenBuilder};
+
+    let (mut token, _) = unsafe {TokenBuilder::<5970>::new()}.token();
+    let cell = Cell::new(0);
+
+    let r = cell.borrow_mut(&mut token);
+    let _alias = unsafe {cell.get()};
+    *r += 1;
+}
+
+#[cfg(all(feature = "paranoid", debug_assertions))]
+#[test]
+fn paranoid_reborrow_test() {
+    use crate::{Cell, TokenBuilder};
+
+    let (mut token, _) = unsafe {TokenBuilder::<5971>::new()}.token();
+    let a = Cell::new(0);
+    let b = Cell::new(0);
+
+    *a.borrow_mut(&mut token) += 1;
+    // Using the token again proves the borrow of `a` is over
+    b.borrow(&token);
+    assert_eq!(unsafe {a.get()}, &1);
+}
+
+#[cfg(all(feature = "paranoid", debug_assertions))]
+#[test]
+fn paranoid_from_mut_test() {
+    use crate::{Cell, TokenBuilder};
+
+    let (token, _) = unsafe {TokenBuilder::<5972>::new()}.token();
+    let mut x = 0;
+
+    {
+        let cell = Cell::<_, 5972>::from_mut(&mut x);
+        cell.borrow(&token);
+    }
+    // A `&mut` proves the earlier borrow is over
+    assert_eq!(Cell::<_, 5972>::from_mut(&mut x).borrow(&token), &0);
+}
@@ -0,0 +1,132 @@
+//! Saving and loading [Arena]s with [serde](::serde). Every entry keeps its slot and generation,
+//! so [WeakIndex]es stored inside entries (or next to the arena, as roots) still point to the same
+//! entries after a round trip, and object graphs built out of indices survive intact.
+//!
+//! With the `arena-brand` feature, loaded indices can't know which arena they came from, so every
+//! loaded arena shares one brand and accepts indices loaded alongside any of the others.
+
+use ::serde::de::Error;
+use ::serde::ser::SerializeStruct;
+use ::serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+use crate::arena::{Arena, WeakIndex};
+use crate::tokens::TokenWith;
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename = "Slot")]
+struct RawSlot<T> {
+    generation: u32,
+    value: Option<T>,
+}
+
+/// A borrowed arena and its roots, ready to be serialized. Created with [save].
+pub struct Snapshot<'a, T, U, const ID: usize> {
+    arena: &'a Arena<T, ID>,
+    roots: &'a [WeakIndex<T, ID>],
+    token: &'a TokenWith<U, ID>,
+}
+
+/// Prepares `arena` to be saved, along with `roots`: the indices the rest of the program needs to
+/// find its way back into the arena after loading it with [Loaded].
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, arena::{Arena, WeakIndex}, persist::{self, Loaded}};
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(Serialize, Deserialize)]
+/// struct Node {
+///     name: String,
+///     next: Option<WeakIndex<Node, 0>>,
+/// }
+///
+/// let (token, _) = first().unwrap().token();
+/// let mut arena = Arena::new();
+/// let tail = arena.insert(Node { name: "tail".into(), next: None });
+/// let head = arena.insert(Node { name: "head".into(), next: Some(tail.downgrade()) });
+///
+/// let json = serde_json::to_string(&persist::save(&arena, &[head.downgrade()], &token)).unwrap();
+/// let Loaded { arena, roots } = serde_json::from_str::<Loaded<Node, 0>>(&json).unwrap();
+///
+/// let head = arena.get(&arena.upgrade(roots[0]).unwrap(), &token);
+/// let tail = arena.upgrade(head.next.unwrap()).unwrap();
+/// assert_eq!(arena.get(&tail, &token).name, "tail");
+/// ```
+pub fn save<'a, T, U, const ID: usize>(
+    arena: &'a Arena<T, ID>,
+    roots: &'a [WeakIndex<T, ID>],
+    token: &'a TokenWith<U, ID>,
+) -> Snapshot<'a, T, U, ID> {
+    Snapshot { arena, roots, token }
+}
+
+impl<T: Serialize, U, const ID: usize> Serialize for Snapshot<'_, T, U, ID> {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        struct Slots<'a, T, U, const ID: usize>(&'a Snapshot<'a, T, U, ID>);
+
+        impl<T: Serialize, U, const ID: usize> Serialize for Slots<'_, T, U, ID> {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                let Snapshot { arena, token, .. } = self.0;
+                serializer.collect_seq(arena.slots(token).map(|(generation, value)| RawSlot { generation, value }))
+            }
+        }
+
+        let mut state = serializer.serialize_struct("Arena", 2)?;
+        state.serialize_field("slots", &Slots(self))?;
+        state.serialize_field("roots", self.roots)?;
+        state.end()
+    }
+}
+
+/// An arena and its roots, loaded from a [Snapshot]. Loading fails if any root doesn't point to a
+/// live entry.
+pub struct Loaded<T, const ID: usize> {
+    pub arena: Arena<T, ID>,
+    pub roots: Vec<WeakIndex<T, ID>>,
+}
+
+impl<'de, T: Deserialize<'de>, const ID: usize> Deserialize<'de> for Loaded<T, ID> {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        #[serde(rename = "Arena", bound = "T: Deserialize<'de>")]
+        struct Raw<T, const ID: usize> {
+            slots: Vec<RawSlot<T>>,
+            roots: Vec<WeakIndex<T, ID>>,
+        }
+
+        let Raw { slots, roots } = Raw::<T, ID>::deserialize(deserializer)?;
+        let arena = Arena::from_slots(slots.into_iter().map(|slot| (slot.generation, slot.value)));
+
+        if let Some(root) = roots.iter().find(|&&root| !arena.contains(root)) {
+            return Err(D::Error::custom(format_args!("root {root:?} doesn't point to a live entry")));
+        }
+
+        Ok(Self { arena, roots })
+    }
+}
+
+#[test]
+fn persist_revalidate_test() {
+    use crate::TokenBuilder;
+
+    let (token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let mut arena = Arena::new();
+    let a = arena.insert(1);
+    let b = arena.insert(2);
+    let (weak_a, weak_b) = (a.downgrade(), b.downgrade());
+    arena.remove(a);
+
+    let json = serde_json::to_string(&save(&arena, &[weak_a], &token)).unwrap();
+    assert!(serde_json::from_str::<Loaded<i32, 1000>>(&json).is_err());
+
+    let json = serde_json::to_string(&save(&arena, &[weak_b], &token)).unwrap();
+    let Loaded { mut arena, roots } = serde_json::from_str::<Loaded<i32, 1000>>(&json).unwrap();
+    assert_eq!(arena.len(), 1);
+    assert!(!arena.contains(weak_a));
+
+    // The removed entry's slot is reused, with a new generation
+    let c = arena.insert(3);
+    assert_eq!(arena.get(&c, &token), &3);
+    assert_eq!(arena.iter(&token).map(|(_, &v)| v).collect::<Vec<_>>(), [3, 2]);
+    assert_eq!(arena.get(&arena.upgrade(roots[0]).unwrap(), &token), &2);
+}
@@ -0,0 +1,205 @@
+//! Interop with [petgraph](::petgraph): an [Arena] whose entries point at each other with
+//! [WeakIndex]es can be viewed as a directed graph, so petgraph's algorithms run on it in place.
+
+use std::collections::HashSet;
+
+use ::petgraph::visit::{
+    Data, GraphBase, GraphRef, IntoEdgeReferences, IntoEdges, IntoNeighbors, IntoNeighborsDirected,
+    IntoNodeIdentifiers, NodeCount, Visitable,
+};
+use ::petgraph::Direction;
+
+use crate::alloc::{Allocator, Global};
+use crate::arena::{Arena, WeakIndex};
+use crate::tokens::TokenWith;
+
+/// A read-only, directed graph view of an [Arena]. Every live entry is a node, and `neighbors`
+/// lists the entries it has edges to; edges to entries that have since been removed are skipped.
+/// Edges have no weights, so algorithms that need costs compute them from the endpoints.
+///
+/// Incoming edges aren't stored anywhere, so algorithms that walk edges backwards (like
+/// `toposort`) have to scan every node to find them.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, arena::{Arena, WeakIndex}, petgraph::ArenaGraph};
+/// use petgraph::algo::{dijkstra, toposort};
+///
+/// struct Node {
+///     next: Vec<WeakIndex<Node, 0>>,
+/// }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let mut arena = Arena::new();
+/// let a = arena.insert(Node { next: Vec::new() });
+/// let b = arena.insert(Node { next: Vec::new() });
+/// let c = arena.insert(Node { next: Vec::new() });
+/// arena.get_mut(&a, &mut token).next.extend([b.downgrade(), c.downgrade()]);
+/// arena.get_mut(&b, &mut token).next.push(c.downgrade());
+///
+/// let graph = ArenaGraph::new(&arena, &token, |node| node.next.iter().copied());
+///
+/// assert_eq!(toposort(graph, None).unwrap(), [a.downgrade(), b.downgrade(), c.downgrade()]);
+/// assert_eq!(dijkstra(graph, a.downgrade(), None, |_| 1)[&c.downgrade()], 1);
+/// ```
+pub struct ArenaGraph<'a, T, U, F, const ID: usize, A: Allocator = Global> {
+    arena: &'a Arena<T, ID, A>,
+    token: &'a TokenWith<U, ID>,
+    neighbors: F,
+}
+
+impl<T, U, F: Copy, const ID: usize, A: Allocator> Clone for ArenaGraph<'_, T, U, F, ID, A> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T, U, F: Copy, const ID: usize, A: Allocator> Copy for ArenaGraph<'_, T, U, F, ID, A> {}
+
+type Nodes<'a, T, const ID: usize> = Box<dyn Iterator<Item = WeakIndex<T, ID>> + 'a>;
+type Edges<'a, T, const ID: usize> = Box<dyn Iterator<Item = Edge<'a, T, ID>> + 'a>;
+type Edge<'a, T, const ID: usize> = (WeakIndex<T, ID>, WeakIndex<T, ID>, &'a ());
+
+impl<'a, T, U, F, I, const ID: usize, A> ArenaGraph<'a, T, U, F, ID, A>
+    where F: Fn(&'a T) -> I + Copy + 'a,
+          I: IntoIterator<Item = WeakIndex<T, ID>>,
+          I::IntoIter: 'a,
+          A: Allocator,
+{
+    pub fn new(arena: &'a Arena<T, ID, A>, token: &'a TokenWith<U, ID>, neighbors: F) -> Self {
+        Self { arena, token, neighbors }
+    }
+
+    fn targets(self, node: WeakIndex<T, ID>) -> Nodes<'a, T, ID> {
+        let arena = self.arena;
+        match arena.upgrade(node) {
+            Some(index) => {
+                let targets = (self.neighbors)(arena.get(&index, self.token)).into_iter();
+                Box::new(targets.filter(move |&target| arena.contains(target)))
+            }
+            None => Box::new(std::iter::empty()),
+        }
+    }
+}
+
+impl<T, U, F, const ID: usize, A: Allocator> GraphBase for ArenaGraph<'_, T, U, F, ID, A> {
+    type NodeId = WeakIndex<T, ID>;
+    type EdgeId = (WeakIndex<T, ID>, WeakIndex<T, ID>);
+}
+
+impl<T, U, F: Copy, const ID: usize, A: Allocator> GraphRef for ArenaGraph<'_, T, U, F, ID, A> {}
+
+impl<T, U, F, const ID: usize, A: Allocator> Data for ArenaGraph<'_, T, U, F, ID, A> {
+    type NodeWeight = T;
+    type EdgeWeight = ();
+}
+
+impl<T, U, F, const ID: usize, A: Allocator> NodeCount for ArenaGraph<'_, T, U, F, ID, A> {
+    fn node_count(&self) -> usize {
+        self.arena.len()
+    }
+}
+
+impl<T, U, F, const ID: usize, A: Allocator> Visitable for ArenaGraph<'_, T, U, F, ID, A> {
+    type Map = HashSet<WeakIndex<T, ID>>;
+
+    fn visit_map(&self) -> Self::Map {
+        HashSet::with_capacity(self.arena.len())
+    }
+
+    fn reset_map(&self, map: &mut Self::Map) {
+        map.clear();
+    }
+}
+
+impl<'a, T, U, F, I, const ID: usize, A> IntoNodeIdentifiers for ArenaGraph<'a, T, U, F, ID, A>
+    where F: Fn(&'a T) -> I + Copy + 'a,
+          I: IntoIterator<Item = WeakIndex<T, ID>>,
+          I::IntoIter: 'a,
+          A: Allocator,
+{
+    type NodeIdentifiers = Nodes<'a, T, ID>;
+
+    fn node_identifiers(self) -> Self::NodeIdentifiers {
+        Box::new(self.arena.iter(self.token).map(|(node, _)| node))
+    }
+}
+
+impl<'a, T, U, F, I, const ID: usize, A> IntoNeighbors for ArenaGraph<'a, T, U, F, ID, A>
+    where F: Fn(&'a T) -> I + Copy + 'a,
+          I: IntoIterator<Item = WeakIndex<T, ID>>,
+          I::IntoIter: 'a,
+          A: Allocator,
+{
+    type Neighbors = Nodes<'a, T, ID>;
+
+    fn neighbors(self, node: WeakIndex<T, ID>) -> Self::Neighbors {
+        self.targets(node)
+    }
+}
+
+impl<'a, T, U, F, I, const ID: usize, A> IntoNeighborsDirected for ArenaGraph<'a, T, U, F, ID, A>
+    where F: Fn(&'a T) -> I + Copy + 'a,
+          I: IntoIterator<Item = WeakIndex<T, ID>>,
+          I::IntoIter: 'a,
+          A: Allocator,
+{
+    type NeighborsDirected = Nodes<'a, T, ID>;
+
+    fn neighbors_directed(self, node: WeakIndex<T, ID>, direction: Direction) -> Self::NeighborsDirected {
+        match direction {
+            Direction::Outgoing => self.targets(node),
+            Direction::Incoming => Box::new(
+                self.node_identifiers().filter(move |&source| self.targets(source).any(|target| target == node)),
+            ),
+        }
+    }
+}
+
+impl<'a, T, U, F, I, const ID: usize, A> IntoEdgeReferences for ArenaGraph<'a, T, U, F, ID, A>
+    where F: Fn(&'a T) -> I + Copy + 'a,
+          I: IntoIterator<Item = WeakIndex<T, ID>>,
+          I::IntoIter: 'a,
+          A: Allocator,
+{
+    type EdgeRef = Edge<'a, T, ID>;
+    type EdgeReferences = Edges<'a, T, ID>;
+
+    fn edge_references(self) -> Self::EdgeReferences {
+        Box::new(self.node_identifiers().flat_map(move |source| self.edges(source)))
+    }
+}
+
+impl<'a, T, U, F, I, const ID: usize, A> IntoEdges for ArenaGraph<'a, T, U, F, ID, A>
+    where F: Fn(&'a T) -> I + Copy + 'a,
+          I: IntoIterator<Item = WeakIndex<T, ID>>,
+          I::IntoIter: 'a,
+          A: Allocator,
+{
+    type Edges = Edges<'a, T, ID>;
+
+    fn edges(self, source: WeakIndex<T, ID>) -> Self::Edges {
+        Box::new(self.targets(source).map(move |target| (source, target, &())))
+    }
+}
+
+#[test]
+fn arena_graph_removed_test() {
+    use crate::TokenBuilder;
+
+    struct Node(Vec<WeakIndex<Node, 1000>>);
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let mut arena = Arena::new();
+    let a = arena.insert(Node(Vec::new()));
+    let b = arena.insert(Node(Vec::new()));
+    let c = arena.insert(Node(Vec::new()));
+    arena.get_mut(&a, &mut token).0.extend([b.downgrade(), c.downgrade()]);
+    let (a, b) = (a.downgrade(), b.downgrade());
+    arena.remove(c);
+
+    let graph = ArenaGraph::new(&arena, &token, |node: &Node| node.0.iter().copied());
+    assert_eq!(graph.neighbors(a).collect::<Vec<_>>(), [b]);
+    assert_eq!(graph.neighbors_directed(b, Direction::Incoming).collect::<Vec<_>>(), [a]);
+    assert_eq!(graph.edge_references().count(), 1);
+}
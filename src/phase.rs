@@ -0,0 +1,81 @@
+//! Typestate phases for tokens. A token's payload can be a phase marker, so that some cells only
+//! accept mutation during a [Setup] phase, and the token has to be explicitly moved on to [Run]
+//! once initialization is done. Ordinary cells accept tokens in any phase.
+
+use std::marker::PhantomData;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// The phase a token starts in, built with `token_with(Setup)`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Setup;
+
+/// The phase a token is in once [Setup] is over.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct Run;
+
+impl<const ID: usize> TokenWith<Setup, ID> {
+    /// Ends the setup phase. There's no way back, so [SetupCell]s can't be mutated again.
+    pub fn run(self) -> TokenWith<Run, ID> {
+        // Safety: the setup token is consumed, so there's still only one token in the family
+        unsafe {TokenWith::new(Run)}
+    }
+}
+
+/// A cell that can be read with a token in any phase, but only mutated with a token in phase `P`.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, phase::{Setup, SetupCell}};
+/// let (mut token, _) = first().unwrap().token_with(Setup);
+///
+/// let handlers = SetupCell::new(Vec::new());
+/// let hits = Cell::new(0);
+///
+/// handlers.borrow_mut(&mut token).push("on_click");
+///
+/// let mut token = token.run();
+/// *hits.borrow_mut(&mut token) += 1;
+/// assert_eq!(handlers.borrow(&token), &["on_click"]);
+/// ```
+///
+/// Once the token has moved on, registration no longer compiles:
+/// ```compile_fail
+/// # use frankencell::{first, phase::{Setup, SetupCell}};
+/// let (token, _) = first().unwrap().token_with(Setup);
+/// let handlers = SetupCell::new(Vec::new());
+///
+/// let mut token = token.run();
+/// handlers.borrow_mut(&mut token).push("too late");
+/// ```
+pub struct PhaseCell<T, P, const ID: usize> {
+    inner: Cell<T, ID>,
+    _phase: PhantomData<fn() -> P>,
+}
+
+/// A cell that can only be mutated during the [Setup] phase.
+pub type SetupCell<T, const ID: usize> = PhaseCell<T, Setup, ID>;
+
+impl<T, P, const ID: usize> PhaseCell<T, P, ID> {
+    pub const fn new(value: T) -> Self {
+        Self { inner: Cell::new(value), _phase: PhantomData }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.inner.into_inner()
+    }
+
+    pub fn borrow<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.inner.borrow(token)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn borrow_mut<'a>(&'a self, token: &'a mut TokenWith<P, ID>) -> &'a mut T {
+        self.inner.borrow_mut(token)
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+}
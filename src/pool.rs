@@ -0,0 +1,178 @@
+use std::marker::PhantomData;
+
+use crate::cells::Cell;
+use crate::sync::{statics::AtomicUsize, Ordering};
+use crate::tokens::TokenWith;
+
+/// What [Pool::acquire] does when every object is already in use.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Exhaustion {
+    /// Create a new object with the pool's factory.
+    Grow,
+    /// Create a new object unless the pool already owns this many, in which case fail.
+    GrowUpTo(usize),
+    /// Never create objects beyond those created with [Pool::prefill].
+    Fail,
+}
+
+/// A handle to an object checked out of a [Pool]. Accessing the object requires both the handle
+/// and the family's token, and giving it back with [Pool::release] consumes the handle.
+#[must_use = "dropping a Pooled without releasing it leaks the object until the pool is dropped"]
+pub struct Pooled<const ID: usize> {
+    slot: usize,
+    pool: usize,
+    _marker: PhantomData<()>,
+}
+
+/// A pool of reusable objects, for objects that are expensive to create (buffers, connections,
+/// entities, ...).
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, pool::{Pool, Exhaustion}};
+/// let (mut token, _) = first().unwrap().token();
+/// let mut buffers = Pool::new(|| Vec::<u8>::with_capacity(1024), Exhaustion::GrowUpTo(2));
+///
+/// let a = buffers.acquire().unwrap();
+/// let b = buffers.acquire().unwrap();
+/// assert!(buffers.acquire().is_none());
+///
+/// buffers.get_mut(&a, &mut token).extend_from_slice(b"hello");
+/// assert_eq!(buffers.get(&a, &token), b"hello");
+///
+/// // `a` goes back into the pool as-is, so clear it before it is reused
+/// buffers.get_mut(&a, &mut token).clear();
+/// buffers.release(a);
+/// assert!(buffers.acquire().is_some());
+/// # buffers.release(b);
+/// ```
+pub struct Pool<T, const ID: usize, F = fn() -> T> {
+    objects: Vec<Cell<T, ID>>,
+    free: Vec<usize>,
+    factory: F,
+    policy: Exhaustion,
+    // Distinguishes this pool's handles from other pools'
+    id: usize,
+}
+
+impl<T, F: FnMut() -> T, const ID: usize> Pool<T, ID, F> {
+    /// Creates an empty pool that uses `factory` to create new objects.
+    pub fn new(factory: F, policy: Exhaustion) -> Self {
+        static NEXT_ID: AtomicUsize = AtomicUsize::new(0);
+
+        Self {
+            objects: Vec::new(),
+            free: Vec::new(),
+            factory,
+            policy,
+            id: NEXT_ID.fetch_add(1, Ordering::Relaxed),
+        }
+    }
+
+    /// Creates `n` objects up front, regardless of the pool's [Exhaustion] policy.
+    pub fn prefill(&mut self, n: usize) {
+        self.objects.reserve(n);
+        for _ in 0..n {
+            self.free.push(self.objects.len());
+            self.objects.push(Cell::new((self.factory)()));
+        }
+    }
+
+    /// Checks an object out of the pool, creating one if none are free and the pool's
+    /// [Exhaustion] policy allows it.
+    pub fn acquire(&mut self) -> Option<Pooled<ID>> {
+        let slot = match self.free.pop() {
+            Some(slot) => slot,
+            None => {
+                let can_grow = match self.policy {
+                    Exhaustion::Grow => true,
+                    Exhaustion::GrowUpTo(max) => self.objects.len() < max,
+                    Exhaustion::Fail => false,
+                };
+                if !can_grow {
+                    return None;
+                }
+
+                self.objects.push(Cell::new((self.factory)()));
+                self.objects.len() - 1
+            }
+        };
+
+        Some(Pooled { slot, pool: self.id, _marker: PhantomData })
+    }
+}
+
+impl<T, F, const ID: usize> Pool<T, ID, F> {
+    /// Returns an object to the pool so a later [Self::acquire] can reuse it.
+    ///
+    /// # Panics
+    /// Panics if `handle` was acquired from a different pool.
+    pub fn release(&mut self, handle: Pooled<ID>) {
+        self.check(&handle);
+        self.free.push(handle.slot);
+    }
+
+    /// Number of objects owned by the pool, whether checked out or not.
+    pub fn len(&self) -> usize {
+        self.objects.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.objects.is_empty()
+    }
+
+    /// Number of objects that can be acquired without creating new ones.
+    pub fn available(&self) -> usize {
+        self.free.len()
+    }
+
+    /// # Panics
+    /// Panics if `handle` was acquired from a different pool.
+    pub fn get<'a, U>(&'a self, handle: &Pooled<ID>, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.check(handle);
+        self.objects[handle.slot].borrow(token)
+    }
+
+    /// # Panics
+    /// Panics if `handle` was acquired from a different pool.
+    #[allow(clippy::mut_from_ref)]
+    pub fn get_mut<'a, U>(&'a self, handle: &Pooled<ID>, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        self.check(handle);
+        self.objects[handle.slot].borrow_mut(token)
+    }
+
+    fn check(&self, handle: &Pooled<ID>) {
+        assert_eq!(handle.pool, self.id, "Pooled handle used with the wrong pool");
+    }
+}
+
+#[test]
+fn pool_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let mut created = 0;
+    let mut pool = Pool::new(|| {created += 1; created}, Exhaustion::Fail);
+
+    assert!(pool.acquire().is_none());
+    pool.prefill(2);
+
+    let a = pool.acquire().unwrap();
+    let b = pool.acquire().unwrap();
+    assert!(pool.acquire().is_none());
+    assert_eq!(pool.available(), 0);
+
+    *pool.get_mut(&b, &mut token) += 10;
+    pool.release(b);
+
+    let c = pool.acquire().unwrap();
+    assert_eq!(*pool.get(&c, &token), 11);
+    assert_ne!(*pool.get(&a, &token), 11);
+    assert_eq!(pool.len(), 2);
+
+    // Another pool's handle to the same slot is rejected instead of aliasing it
+    let mut other = Pool::new(|| 0, Exhaustion::Grow);
+    let foreign = other.acquire().unwrap();
+    assert!(std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| pool.release(foreign))).is_err());
+    assert_eq!(pool.available(), 0);
+}
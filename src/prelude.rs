@@ -0,0 +1,22 @@
+//! The most commonly used items, for a single glob import.
+//!
+//! # Example
+//! ```rust
+//! use frankencell::prelude::*;
+//!
+//! init_tokens! { after first().unwrap();
+//!     token then _next
+//! }
+//!
+//! let mut cells = vec![Cell::new(3), Cell::new(1), Cell::new(2)];
+//! cells.sort_with(&token);
+//!
+//! assert_eq!(cells.iter().borrowed(&token).copied().collect::<Vec<_>>(), [1, 2, 3]);
+//! ```
+
+pub use crate::{first, init_tokens};
+pub use crate::builder::TokenBuilder;
+pub use crate::cells::Cell;
+pub use crate::iter::{CollectCells, IterWith};
+pub use crate::slice::CellSliceExt;
+pub use crate::tokens::{Token, TokenWith};
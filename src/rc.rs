@@ -0,0 +1,108 @@
+use std::marker::PhantomData;
+use std::ops::Deref;
+use std::ptr::NonNull;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+struct Inner<T, const ID: usize> {
+    count: Cell<usize, ID>,
+    value: T,
+}
+
+/// A reference-counted pointer whose count is guarded by a token instead of a `std::cell::Cell`
+/// or an atomic: cloning and releasing require a `&mut Token`. Since only one thread can hold the
+/// token mutably, the count never needs atomic instructions, yet a `TokenRc` can still be sent
+/// between threads.
+///
+/// Dropping a `TokenRc` without a token can't update the count, so it leaks its share of the
+/// value instead; use [Self::release] to give it back.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, rc::TokenRc};
+/// let (mut token, _) = first().unwrap().token();
+/// let a = TokenRc::new(String::from("shared"));
+/// let b = a.clone_with(&mut token);
+///
+/// let b = std::thread::spawn(move || {
+///     assert_eq!(*b, "shared");
+///     b
+/// }).join().unwrap();
+///
+/// assert_eq!(a.strong_count(&token), 2);
+/// assert_eq!(b.release(&mut token), None);
+/// assert_eq!(a.release(&mut token).as_deref(), Some("shared"));
+/// ```
+#[must_use = "dropping a TokenRc without releasing it leaks the value"]
+pub struct TokenRc<T, const ID: usize> {
+    ptr: NonNull<Inner<T, ID>>,
+    _marker: PhantomData<Inner<T, ID>>,
+}
+
+/// [TokenRc] never uses atomics, so it is already safe to share between threads; this alias is
+/// only provided for discoverability.
+pub type TokenArc<T, const ID: usize> = TokenRc<T, ID>;
+
+// Safety: the count is only modified through a `&mut Token`, which can only exist on one thread at
+// a time. The value itself is shared, hence the same bounds as `Arc`.
+unsafe impl<T: Send + Sync, const ID: usize> Send for TokenRc<T, ID> {}
+unsafe impl<T: Send + Sync, const ID: usize> Sync for TokenRc<T, ID> {}
+
+impl<T, const ID: usize> TokenRc<T, ID> {
+    pub fn new(value: T) -> Self {
+        let inner = Box::new(Inner {
+            count: Cell::new(1),
+            value,
+        });
+
+        Self {
+            ptr: NonNull::from(Box::leak(inner)),
+            _marker: PhantomData,
+        }
+    }
+
+    fn inner(&self) -> &Inner<T, ID> {
+        unsafe {self.ptr.as_ref()}
+    }
+
+    /// Creates another pointer to the same value, incrementing the count.
+    pub fn clone_with<U>(&self, token: &mut TokenWith<U, ID>) -> Self {
+        *self.inner().count.borrow_mut(token) += 1;
+
+        Self {
+            ptr: self.ptr,
+            _marker: PhantomData,
+        }
+    }
+
+    /// Gives up this pointer, decrementing the count. If it was the last one, the value is
+    /// returned.
+    pub fn release<U>(self, token: &mut TokenWith<U, ID>) -> Option<T> {
+        let count = self.inner().count.borrow_mut(token);
+        *count -= 1;
+
+        if *count == 0 {
+            let inner = unsafe {Box::from_raw(self.ptr.as_ptr())};
+            Some(inner.value)
+        } else {
+            None
+        }
+    }
+
+    pub fn strong_count<U>(&self, token: &TokenWith<U, ID>) -> usize {
+        *self.inner().count.borrow(token)
+    }
+
+    pub fn ptr_eq(&self, other: &Self) -> bool {
+        self.ptr == other.ptr
+    }
+}
+
+impl<T, const ID: usize> Deref for TokenRc<T, ID> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.inner().value
+    }
+}
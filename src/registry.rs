@@ -0,0 +1,116 @@
+//! An opt-in inspector for large apps: cells registered by name in a [CellRegistry] can be
+//! pretty-printed all at once with [CellRegistry::dump_state], e.g. from a debug command or a
+//! panic hook.
+
+use std::fmt::{self, Debug, Write};
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+// Lets cells of different types share one list. The token can't be passed through a trait
+// object's method generically, so the value comes back as a pointer that's only read while the
+// token is borrowed.
+trait Dump<const ID: usize> {
+    fn value(&self) -> *const (dyn Debug + '_);
+}
+
+impl<T: Debug, const ID: usize> Dump<ID> for Cell<T, ID> {
+    fn value(&self) -> *const (dyn Debug + '_) {
+        self.as_ptr()
+    }
+}
+
+/// A list of named cells from one family, in the order they were registered.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, registry::CellRegistry};
+/// let (mut token, _) = first().unwrap().token();
+/// let user = Cell::new("ferris");
+/// let retries = Cell::new(0);
+///
+/// let mut registry = CellRegistry::new();
+/// registry.register("user", &user);
+/// registry.register("retries", &retries);
+///
+/// *retries.borrow_mut(&mut token) += 2;
+///
+/// let mut dump = String::new();
+/// registry.dump_state(&token, &mut dump).unwrap();
+/// assert_eq!(dump, "user = \"ferris\"\nretries = 2\n");
+/// ```
+pub struct CellRegistry<'a, const ID: usize> {
+    cells: Vec<(String, &'a dyn Dump<ID>)>,
+}
+
+impl<const ID: usize> Default for CellRegistry<'_, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<'a, const ID: usize> CellRegistry<'a, ID> {
+    pub fn new() -> Self {
+        Self {
+            cells: Vec::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Adds `cell` to the registry under `name`. Names don't have to be unique.
+    pub fn register<T: Debug>(&mut self, name: impl Into<String>, cell: &'a Cell<T, ID>) {
+        self.cells.push((name.into(), cell));
+    }
+
+    /// Removes every cell registered under `name`, returning how many there were.
+    pub fn unregister(&mut self, name: &str) -> usize {
+        let len = self.cells.len();
+        self.cells.retain(|(n, _)| n != name);
+        len - self.cells.len()
+    }
+
+    /// The registered names, in order.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.cells.iter().map(|(name, _)| name.as_str())
+    }
+
+    /// Writes every registered cell as `name = value`, one per line, with values pretty-printed
+    /// using their [Debug] impls.
+    pub fn dump_state<U>(&self, _: &TokenWith<U, ID>, out: &mut impl Write) -> fmt::Result {
+        for (name, cell) in &self.cells {
+            // Safety: the token is borrowed for as long as the value is
+            let value = unsafe {&*cell.value()};
+            writeln!(out, "{name} = {value:#?}")?;
+        }
+        Ok(())
+    }
+}
+
+impl<const ID: usize> Debug for CellRegistry<'_, ID> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_list().entries(self.names()).finish()
+    }
+}
+
+#[test]
+fn registry_token_with_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token_with(String::from("data"));
+    let names = Cell::new(vec!["a"]);
+
+    let mut registry = CellRegistry::new();
+    registry.register("names", &names);
+    names.borrow_mut(&mut token).push("b");
+
+    let mut dump = String::new();
+    registry.dump_state(&token, &mut dump).unwrap();
+    assert_eq!(dump, "names = [\n    \"a\",\n    \"b\",\n]\n");
+}
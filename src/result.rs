@@ -0,0 +1,60 @@
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// Projections for fallible state stored in a cell, so it can be inspected or patched up without
+/// cloning the whole `Result` out first.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell};
+/// let (mut token, _) = first().unwrap().token();
+/// let cached: Cell<Result<u16, String>, 0> = Cell::new("8080x".parse().map_err(|e| format!("{}", e)));
+///
+/// assert!(cached.is_err(&token));
+/// cached.map_err_in_place(&mut token, |e| e.insert_str(0, "invalid port: "));
+/// assert_eq!(cached.as_ref_err(&token).unwrap(), "invalid port: invalid digit found in string");
+///
+/// cached.set(&mut token, Ok(8080));
+/// assert_eq!(cached.as_ref_ok(&token), Some(&8080));
+/// ```
+impl<T, E, const ID: usize> Cell<Result<T, E>, ID> {
+    pub fn is_ok<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.borrow(token).is_ok()
+    }
+
+    pub fn is_err<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.borrow(token).is_err()
+    }
+
+    pub fn as_ref_ok<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> Option<&'a T> {
+        self.borrow(token).as_ref().ok()
+    }
+
+    pub fn as_ref_err<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> Option<&'a E> {
+        self.borrow(token).as_ref().err()
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn as_mut_ok<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> Option<&'a mut T> {
+        self.borrow_mut(token).as_mut().ok()
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn as_mut_err<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> Option<&'a mut E> {
+        self.borrow_mut(token).as_mut().err()
+    }
+
+    /// Calls `f` on the value if the cell holds `Ok`.
+    pub fn map_ok_in_place<U>(&self, token: &mut TokenWith<U, ID>, f: impl FnOnce(&mut T)) {
+        if let Some(value) = self.as_mut_ok(token) {
+            f(value);
+        }
+    }
+
+    /// Calls `f` on the error if the cell holds `Err`.
+    pub fn map_err_in_place<U>(&self, token: &mut TokenWith<U, ID>, f: impl FnOnce(&mut E)) {
+        if let Some(error) = self.as_mut_err(token) {
+            f(error);
+        }
+    }
+}
@@ -0,0 +1,50 @@
+//! Integration with [RTIC](https://rtic.rs) shared resources.
+//!
+//! Instead of one shared resource per piece of state, put a family's token in a single shared
+//! resource and keep the family's cells in `static`s. RTIC computes the token resource's priority
+//! ceiling from the tasks that list it, so every task that touches the family's cells has to lock
+//! it, and borrowing the cells inside the lock is checked at compile time. Group state by which
+//! tasks share it: one family, and one token resource, per priority ceiling.
+
+use rtic_core::Mutex;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// Borrows cells through a locked token resource. Implemented for every RTIC resource proxy
+/// holding a [TokenWith].
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, rtic::TokenResource};
+/// use rtic_core::{Exclusive, Mutex};
+///
+/// static COUNT: Cell<u32, 0> = Cell::new(0);
+/// static LAST: Cell<u32, 0> = Cell::new(0);
+///
+/// // In a task with `shared = [token]`, this would be `cx.shared.token`
+/// fn on_sample(mut token: impl Mutex<T = frankencell::Token<0>>, sample: u32) {
+///     token.with_cell_mut(&COUNT, |count| *count += 1);
+///     token.lock(|token| *LAST.borrow_mut(token) = sample);
+/// }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// on_sample(Exclusive(&mut token), 7);
+/// on_sample(Exclusive(&mut token), 9);
+///
+/// assert_eq!(COUNT.borrow(&token), &2);
+/// assert_eq!(LAST.borrow(&token), &9);
+/// ```
+pub trait TokenResource<U, const ID: usize>: Mutex<T = TokenWith<U, ID>> {
+    /// Locks the token and runs `f` with shared access to `cell`'s contents.
+    fn with_cell<T, R>(&mut self, cell: &Cell<T, ID>, f: impl FnOnce(&T) -> R) -> R {
+        self.lock(|token| f(cell.borrow(token)))
+    }
+
+    /// Locks the token and runs `f` with mutable access to `cell`'s contents.
+    fn with_cell_mut<T, R>(&mut self, cell: &Cell<T, ID>, f: impl FnOnce(&mut T) -> R) -> R {
+        self.lock(|token| f(cell.borrow_mut(token)))
+    }
+}
+
+impl<M: Mutex<T = TokenWith<U, ID>>, U, const ID: usize> TokenResource<U, ID> for M {}
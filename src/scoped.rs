@@ -0,0 +1,82 @@
+//! Helpers for handing token access to [std::thread::scope] threads. Each helper borrows the token
+//! for the whole scope, so it can't be used again until every thread has been joined and
+//! everything borrowed through it has been given back.
+//!
+//! Shared access needs no helper: a `&TokenWith` can already be copied into as many scoped
+//! threads as needed.
+
+use std::sync::Arc;
+use std::thread::{Scope, ScopedJoinHandle};
+
+use crate::cells::Cell;
+use crate::slice::CellSliceExt;
+use crate::tokens::TokenWith;
+
+/// Spawns a scoped thread with exclusive access to the token.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, scoped};
+/// let (mut token, _) = first().unwrap().token();
+/// let log = Cell::new(Vec::new());
+///
+/// std::thread::scope(|s| {
+///     scoped::lend(s, &mut token, |token| log.borrow_mut(token).push("from a thread"));
+/// });
+///
+/// assert_eq!(log.borrow(&token).len(), 1);
+/// ```
+pub fn lend<'scope, 'env, U, R, F, const ID: usize>(
+    scope: &'scope Scope<'scope, 'env>,
+    token: &'scope mut TokenWith<U, ID>,
+    f: F,
+) -> ScopedJoinHandle<'scope, R>
+where
+    U: Send,
+    R: Send + 'scope,
+    F: FnOnce(&mut TokenWith<U, ID>) -> R + Send + 'scope,
+{
+    scope.spawn(move || f(token))
+}
+
+/// Splits `cells` into chunks of `chunk_size` and spawns a scoped thread per chunk, each with
+/// mutable access to its own chunk's contents. `f` is also given the index of the chunk's first
+/// cell.
+///
+/// # Panics
+/// Panics if `chunk_size` is 0.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, scoped};
+/// let (mut token, _) = first().unwrap().token();
+/// let cells: Vec<_> = (0..10).map(Cell::new).collect();
+///
+/// std::thread::scope(|s| {
+///     scoped::for_each_chunk_mut(s, &mut token, &cells, 3, |_, chunk| {
+///         chunk.iter_mut().for_each(|x| *x *= 2);
+///     });
+/// });
+///
+/// assert_eq!(cells[9].borrow(&token), &18);
+/// ```
+pub fn for_each_chunk_mut<'scope, 'env, T, U, F, const ID: usize>(
+    scope: &'scope Scope<'scope, 'env>,
+    token: &'scope mut TokenWith<U, ID>,
+    cells: &'scope [Cell<T, ID>],
+    chunk_size: usize,
+    f: F,
+) where
+    T: Send,
+    F: Fn(usize, &mut [T]) + Send + Sync + 'scope,
+{
+    // The token stays borrowed for the whole scope, so nothing else can access the cells until
+    // every thread has been joined
+    let values = cells.as_inner_slice_mut(token);
+
+    let f = Arc::new(f);
+    for (i, chunk) in values.chunks_mut(chunk_size).enumerate() {
+        let f = Arc::clone(&f);
+        scope.spawn(move || f(i * chunk_size, chunk));
+    }
+}
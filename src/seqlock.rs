@@ -0,0 +1,165 @@
+use std::mem::MaybeUninit;
+
+use crate::sync::{fence, spin_loop, AtomicUsize, Ordering};
+use crate::tokens::TokenWith;
+
+/// A cell for small `Copy` values that any thread can read without the token, using a sequence
+/// lock: [Self::read] copies the value out and retries if a write happened in the meantime. Writes
+/// still need the family's `&mut Token`, so there is only ever one writer. Meant for telemetry,
+/// counters and other values that are written by one thread and polled by many.
+///
+/// Reads never block writes, but a reader can spin for as long as writes keep happening.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, seqlock::SeqCell};
+/// #[derive(Clone, Copy, PartialEq, Debug)]
+/// struct Stats { frames: u64, dropped: u64 }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let stats = SeqCell::new(Stats { frames: 0, dropped: 0 });
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         // Always a consistent snapshot, never half of an update
+///         let snapshot = stats.read();
+///         assert_eq!(snapshot.frames, snapshot.dropped * 2);
+///     });
+///
+///     for _ in 0..1000 {
+///         stats.update(&mut token, |s| Stats { frames: s.frames + 2, dropped: s.dropped + 1 });
+///     }
+/// });
+///
+/// assert_eq!(stats.get(&token), Stats { frames: 2000, dropped: 1000 });
+/// ```
+pub struct SeqCell<T: Copy, const ID: usize> {
+    // Odd while a write is in progress
+    sequence: AtomicUsize,
+    value: Racy<T>,
+}
+
+// Safety: values are only ever copied out, and writes are serialized by the token
+unsafe impl<T: Copy + Send, const ID: usize> Sync for SeqCell<T, ID> {}
+
+impl<T: Copy, const ID: usize> SeqCell<T, ID> {
+    #[cfg(not(loom))]
+    pub const fn new(value: T) -> Self {
+        Self {
+            sequence: AtomicUsize::new(0),
+            value: Racy::new(value),
+        }
+    }
+
+    // loom's atomics can't be created in a const context
+    #[cfg(loom)]
+    pub fn new(value: T) -> Self {
+        Self {
+            sequence: AtomicUsize::new(0),
+            value: Racy::new(value),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        unsafe {self.value.read().assume_init()}
+    }
+
+    /// Copies the value out without the token, retrying until no write overlaps the copy.
+    pub fn read(&self) -> T {
+        loop {
+            let before = self.sequence.load(Ordering::Acquire);
+            if before % 2 == 1 {
+                spin_loop();
+                continue;
+            }
+
+            // Safety: the copy may be torn by a concurrent write, so it stays a `MaybeUninit`
+            // until the sequence number confirms that it wasn't
+            let value = unsafe {self.value.read()};
+
+            fence(Ordering::Acquire);
+            if self.sequence.load(Ordering::Relaxed) == before {
+                return unsafe {value.assume_init()};
+            }
+        }
+    }
+
+    /// Reads the value directly; holding the token rules out a concurrent write.
+    pub fn get<U>(&self, _: &TokenWith<U, ID>) -> T {
+        unsafe {self.value.read().assume_init()}
+    }
+
+    pub fn set<U>(&self, _: &mut TokenWith<U, ID>, value: T) {
+        let sequence = self.sequence.load(Ordering::Relaxed);
+        self.sequence.store(sequence.wrapping_add(1), Ordering::Relaxed);
+        fence(Ordering::Release);
+
+        // Safety: the token serializes writers
+        unsafe {self.value.write(value)};
+
+        self.sequence.store(sequence.wrapping_add(2), Ordering::Release);
+    }
+
+    pub fn update<U>(&self, token: &mut TokenWith<U, ID>, f: impl FnOnce(T) -> T) {
+        let value = f(self.get(token));
+        self.set(token, value);
+    }
+}
+
+/// The value of a [SeqCell], which readers copy out while it may be being overwritten.
+#[cfg(not(loom))]
+struct Racy<T>(crate::sync::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T: Copy> Racy<T> {
+    const fn new(value: T) -> Self {
+        Self(crate::sync::UnsafeCell::new(value))
+    }
+
+    /// Safety: the result is only initialized if no write overlapped the copy.
+    unsafe fn read(&self) -> MaybeUninit<T> {
+        self.0.with_mut(|value| unsafe {std::ptr::read_volatile(value as *const MaybeUninit<T>)})
+    }
+
+    /// Safety: there must be no concurrent writes.
+    unsafe fn write(&self, value: T) {
+        self.0.with_mut(|old| unsafe {std::ptr::write_volatile(old, value)});
+    }
+}
+
+/// Under loom, the value is copied a byte at a time through relaxed atomics, so the model can
+/// interleave a read with a write (which an `UnsafeCell` would report as a data race) and check that
+/// the sequence number catches every torn copy.
+#[cfg(loom)]
+struct Racy<T> {
+    bytes: Box<[loom::sync::atomic::AtomicU8]>,
+    _type: std::marker::PhantomData<T>,
+}
+
+#[cfg(loom)]
+impl<T: Copy> Racy<T> {
+    fn new(value: T) -> Self {
+        let racy = Self {
+            bytes: (0..size_of::<T>()).map(|_| loom::sync::atomic::AtomicU8::new(0)).collect(),
+            _type: std::marker::PhantomData,
+        };
+        unsafe {racy.write(value)};
+        racy
+    }
+
+    unsafe fn read(&self) -> MaybeUninit<T> {
+        let mut value = MaybeUninit::<T>::uninit();
+        let out = value.as_mut_ptr() as *mut u8;
+        for (i, byte) in self.bytes.iter().enumerate() {
+            unsafe {out.add(i).write(byte.load(Ordering::Relaxed))};
+        }
+        value
+    }
+
+    unsafe fn write(&self, value: T) {
+        let bytes = &value as *const T as *const u8;
+        for (i, byte) in self.bytes.iter().enumerate() {
+            byte.store(unsafe {bytes.add(i).read()}, Ordering::Relaxed);
+        }
+    }
+}
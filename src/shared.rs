@@ -0,0 +1,89 @@
+use std::fmt::Debug;
+use std::ops::Deref;
+use std::sync::Arc;
+
+use crate::tokens::TokenWith;
+
+/// A token that has been given up for shared, read-only use by several owners at once. Each clone
+/// derefs to a `&TokenWith`, so it can be passed straight to [Cell::borrow](crate::Cell::borrow)
+/// from any number of threads. The number of live clones is counted at runtime, and once only
+/// one is left, [Self::try_upgrade] turns it back into the exclusive token.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, shared::SharedToken};
+/// let (mut token, _) = first().unwrap().token();
+/// let config = Cell::new(vec!["a", "b"]);
+///
+/// let shared = SharedToken::new(token);
+/// std::thread::scope(|s| {
+///     for _ in 0..4 {
+///         let reader = shared.clone();
+///         let config = &config;
+///         s.spawn(move || assert_eq!(config.borrow(&reader).len(), 2));
+///     }
+/// });
+///
+/// let mut token = shared.try_upgrade().unwrap();
+/// config.borrow_mut(&mut token).push("c");
+/// ```
+pub struct SharedTokenWith<U, const ID: usize> {
+    token: Arc<TokenWith<U, ID>>,
+}
+
+/// A [SharedTokenWith] for a plain [Token](crate::tokens::Token).
+pub type SharedToken<const ID: usize> = SharedTokenWith<(), ID>;
+
+impl<U, const ID: usize> SharedTokenWith<U, ID> {
+    pub fn new(token: TokenWith<U, ID>) -> Self {
+        Self {
+            token: Arc::new(token),
+        }
+    }
+
+    /// Number of live handles, including this one.
+    pub fn readers(&self) -> usize {
+        Arc::strong_count(&self.token)
+    }
+
+    /// Takes back exclusive access if this is the last handle, otherwise gives `self` back.
+    pub fn try_upgrade(self) -> Result<TokenWith<U, ID>, Self> {
+        Arc::try_unwrap(self.token).map_err(|token| Self { token })
+    }
+}
+
+impl<U, const ID: usize> Clone for SharedTokenWith<U, ID> {
+    fn clone(&self) -> Self {
+        Self {
+            token: Arc::clone(&self.token),
+        }
+    }
+}
+
+impl<U, const ID: usize> Deref for SharedTokenWith<U, ID> {
+    type Target = TokenWith<U, ID>;
+
+    fn deref(&self) -> &TokenWith<U, ID> {
+        &self.token
+    }
+}
+
+impl<U: Debug, const ID: usize> Debug for SharedTokenWith<U, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple(&format!("SharedTokenWith<{}>", ID)).field(&self.token.0).finish()
+    }
+}
+
+#[test]
+fn shared_upgrade_test() {
+    use crate::TokenBuilder;
+
+    let (token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let a = SharedToken::new(token);
+    let b = a.clone();
+    assert_eq!(a.readers(), 2);
+
+    let a = a.try_upgrade().err().unwrap();
+    drop(b);
+    assert!(a.try_upgrade().is_ok());
+}
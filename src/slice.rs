@@ -0,0 +1,107 @@
+use std::cmp::Ordering;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// Bulk operations on slices of cells that compare the cells' contents directly, without first
+/// collecting a `Vec<&T>`.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, slice::CellSliceExt};
+/// let (token, _) = first().unwrap().token();
+/// let mut cells = [Cell::new(3), Cell::new(1), Cell::new(2)];
+///
+/// cells.sort_with(&token);
+/// assert_eq!(cells.binary_search_with(&token, &2), Ok(1));
+/// assert_eq!(cells.binary_search_with(&token, &5), Err(3));
+/// ```
+pub trait CellSliceExt<T, const ID: usize> {
+    /// See [slice::sort]
+    fn sort_with<U>(&mut self, token: &TokenWith<U, ID>) where T: Ord;
+
+    /// See [slice::sort_by]
+    fn sort_by_with<U, F>(&mut self, token: &TokenWith<U, ID>, compare: F)
+        where F: FnMut(&T, &T) -> Ordering;
+
+    /// See [slice::sort_by_key]
+    fn sort_by_key_with<U, K, F>(&mut self, token: &TokenWith<U, ID>, f: F)
+        where F: FnMut(&T) -> K,
+              K: Ord;
+
+    /// See [slice::sort_unstable]
+    fn sort_unstable_with<U>(&mut self, token: &TokenWith<U, ID>) where T: Ord;
+
+    /// See [slice::binary_search]
+    fn binary_search_with<U>(&self, token: &TokenWith<U, ID>, x: &T) -> Result<usize, usize>
+        where T: Ord;
+
+    /// See [slice::binary_search_by]
+    fn binary_search_by_with<U, F>(&self, token: &TokenWith<U, ID>, f: F) -> Result<usize, usize>
+        where F: FnMut(&T) -> Ordering;
+
+    /// Views the whole slice as a `&[T]`, e.g. to pass it to an API that takes plain slices.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell, slice::CellSliceExt};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let bytes: Vec<Cell<u8, 0>> = b"hello".iter().copied().map(Cell::new).collect();
+    ///
+    /// bytes.as_inner_slice_mut(&mut token).make_ascii_uppercase();
+    /// assert_eq!(std::str::from_utf8(bytes.as_inner_slice(&token)), Ok("HELLO"));
+    /// ```
+    fn as_inner_slice<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a [T];
+
+    /// Views the whole slice as a `&mut [T]`.
+    #[allow(clippy::mut_from_ref)]
+    fn as_inner_slice_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut [T];
+}
+
+impl<T, const ID: usize> CellSliceExt<T, ID> for [Cell<T, ID>] {
+    fn sort_with<U>(&mut self, token: &TokenWith<U, ID>) where T: Ord {
+        self.sort_by(|a, b| a.borrow(token).cmp(b.borrow(token)))
+    }
+
+    fn sort_by_with<U, F>(&mut self, token: &TokenWith<U, ID>, mut compare: F)
+        where F: FnMut(&T, &T) -> Ordering
+    {
+        self.sort_by(|a, b| compare(a.borrow(token), b.borrow(token)))
+    }
+
+    fn sort_by_key_with<U, K, F>(&mut self, token: &TokenWith<U, ID>, mut f: F)
+        where F: FnMut(&T) -> K,
+              K: Ord
+    {
+        self.sort_by_key(|a| f(a.borrow(token)))
+    }
+
+    fn sort_unstable_with<U>(&mut self, token: &TokenWith<U, ID>) where T: Ord {
+        self.sort_unstable_by(|a, b| a.borrow(token).cmp(b.borrow(token)))
+    }
+
+    fn binary_search_with<U>(&self, token: &TokenWith<U, ID>, x: &T) -> Result<usize, usize>
+        where T: Ord
+    {
+        self.binary_search_by(|a| a.borrow(token).cmp(x))
+    }
+
+    fn binary_search_by_with<U, F>(&self, token: &TokenWith<U, ID>, mut f: F) -> Result<usize, usize>
+        where F: FnMut(&T) -> Ordering
+    {
+        self.binary_search_by(|a| f(a.borrow(token)))
+    }
+
+    fn as_inner_slice<'a, U>(&'a self, _: &'a TokenWith<U, ID>) -> &'a [T] {
+        // Safety: `Cell<T, ID>` is `repr(transparent)` over `T`, and the shared token rules out
+        // a `&mut T` to any of the cells
+        unsafe {&*(self as *const [Cell<T, ID>] as *const [T])}
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    fn as_inner_slice_mut<'a, U>(&'a self, _: &'a mut TokenWith<U, ID>) -> &'a mut [T] {
+        // Safety: as above, and the mutable token rules out any other borrow of the cells
+        let first = std::cell::UnsafeCell::raw_get(self.as_ptr() as *const std::cell::UnsafeCell<T>);
+        unsafe {&mut *std::ptr::slice_from_raw_parts_mut(first, self.len())}
+    }
+}
@@ -0,0 +1,103 @@
+//! A [smallvec](::smallvec)-backed vector of token-gated elements.
+
+use ::smallvec::SmallVec;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// A vector that stores up to `N` elements inline before spilling onto the heap, and that can be
+/// read with a `&Token` and changed with a `&mut Token`, like [TokenString](crate::string::TokenString).
+/// Useful on hot paths where most vectors stay small and an allocation per vector is too
+/// expensive.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, smallvec::SmallCellVec};
+/// let (mut token, _) = first().unwrap().token();
+/// let children: SmallCellVec<u32, 2, 0> = SmallCellVec::new();
+///
+/// children.push(&mut token, 1);
+/// children.push(&mut token, 2);
+/// assert!(!children.spilled(&token));
+///
+/// children.push(&mut token, 3);
+/// assert!(children.spilled(&token));
+/// assert_eq!(children.as_slice(&token), [1, 2, 3]);
+/// ```
+#[repr(transparent)]
+pub struct SmallCellVec<T, const N: usize, const ID: usize> {
+    inner: Cell<SmallVec<[T; N]>, ID>,
+}
+
+impl<T, const N: usize, const ID: usize> Default for SmallCellVec<T, N, ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T, const N: usize, const ID: usize> SmallCellVec<T, N, ID> {
+    pub const fn new() -> Self {
+        Self {
+            inner: Cell::new(SmallVec::new_const()),
+        }
+    }
+
+    pub fn into_inner(self) -> SmallVec<[T; N]> {
+        self.inner.into_inner()
+    }
+
+    pub fn as_slice<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a [T] {
+        self.inner.borrow(token)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn as_mut_slice<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut [T] {
+        self.inner.borrow_mut(token)
+    }
+
+    /// Mutable access to the underlying `SmallVec`, for anything not covered by the methods below.
+    #[allow(clippy::mut_from_ref)]
+    pub fn as_mut_smallvec<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut SmallVec<[T; N]> {
+        self.inner.borrow_mut(token)
+    }
+
+    pub fn len<U>(&self, token: &TokenWith<U, ID>) -> usize {
+        self.inner.borrow(token).len()
+    }
+
+    pub fn is_empty<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.inner.borrow(token).is_empty()
+    }
+
+    /// Whether the elements have outgrown the inline storage and moved to the heap.
+    pub fn spilled<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.inner.borrow(token).spilled()
+    }
+
+    pub fn push<U>(&self, token: &mut TokenWith<U, ID>, value: T) {
+        self.as_mut_smallvec(token).push(value)
+    }
+
+    pub fn pop<U>(&self, token: &mut TokenWith<U, ID>) -> Option<T> {
+        self.as_mut_smallvec(token).pop()
+    }
+
+    /// See [SmallVec::insert]
+    pub fn insert<U>(&self, token: &mut TokenWith<U, ID>, index: usize, value: T) {
+        self.as_mut_smallvec(token).insert(index, value)
+    }
+
+    /// See [SmallVec::remove]
+    pub fn remove<U>(&self, token: &mut TokenWith<U, ID>, index: usize) -> T {
+        self.as_mut_smallvec(token).remove(index)
+    }
+
+    /// See [SmallVec::truncate]
+    pub fn truncate<U>(&self, token: &mut TokenWith<U, ID>, len: usize) {
+        self.as_mut_smallvec(token).truncate(len)
+    }
+
+    pub fn clear<U>(&self, token: &mut TokenWith<U, ID>) {
+        self.as_mut_smallvec(token).clear()
+    }
+}
@@ -0,0 +1,109 @@
+use std::marker::PhantomData;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// Implemented only for [Cell]. Taking fields as a generic `&C` rather than `&Cell` stops them
+/// from being deref-coerced, so two fields holding `&Cell`s to the same cell are rejected.
+#[doc(hidden)]
+pub unsafe trait SplitField<const ID: usize> {
+    type Inner;
+}
+
+unsafe impl<T, const ID: usize> SplitField<ID> for Cell<T, ID> {
+    type Inner = T;
+}
+
+/// Holds a token's mutable borrow for [split_borrow!](crate::split_borrow) so every field
+/// borrowed through it keeps the token locked. Not part of the public API.
+#[doc(hidden)]
+pub struct SplitBorrow<'a, U, const ID: usize> {
+    _token: PhantomData<&'a mut TokenWith<U, ID>>,
+}
+
+impl<'a, U, const ID: usize> SplitBorrow<'a, U, ID> {
+    pub fn new(_: &'a mut TokenWith<U, ID>) -> Self {
+        Self { _token: PhantomData }
+    }
+
+    /// # Safety
+    /// Must not be called twice for the same cell.
+    #[allow(clippy::mut_from_ref)]
+    pub unsafe fn borrow_mut<C: SplitField<ID>>(&self, cell: &'a C) -> &'a mut C::Inner {
+        // `Cell` is `repr(transparent)` over its value
+        unsafe {(cell as *const C).cast::<C::Inner>().cast_mut().as_mut().unwrap_unchecked()}
+    }
+}
+
+/// Mutably borrows several cell fields of a struct at once with a single `&mut Token`. Listing
+/// the same field twice doesn't compile, since each field becomes a binding of the same name.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, split_borrow};
+/// struct Player<const ID: usize> {
+///     health: Cell<u32, ID>,
+///     inventory: Cell<Vec<&'static str>, ID>,
+/// }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let player = Player { health: Cell::new(10), inventory: Cell::new(vec!["potion"]) };
+///
+/// split_borrow!(let {health, inventory} = player, &mut token);
+/// if inventory.pop() == Some("potion") {
+///     *health += 5;
+/// }
+///
+/// assert_eq!(player.health.borrow(&token), &15);
+/// ```
+///
+/// ```compile_fail
+/// # use frankencell::{first, Cell, split_borrow};
+/// # struct Player<const ID: usize> { health: Cell<u32, ID> }
+/// let (mut token, _) = first().unwrap().token();
+/// let player = Player { health: Cell::new(10) };
+///
+/// split_borrow!(let {health, health} = player, &mut token);
+/// ```
+///
+/// Fields must be cells themselves, not references to them, since those could point to the same
+/// cell:
+/// ```compile_fail
+/// # use frankencell::{first, Cell, split_borrow};
+/// struct Aliased<'a, const ID: usize> { a: &'a Cell<u32, ID>, b: &'a Cell<u32, ID> }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let cell = Cell::new(0);
+/// let aliased = Aliased { a: &cell, b: &cell };
+///
+/// split_borrow!(let {a, b} = aliased, &mut token);
+/// ```
+#[macro_export]
+macro_rules! split_borrow {
+    (let {$($field:ident),+ $(,)?} = $s:expr, $token:expr) => {
+        let ($($field,)+) = {
+            let s = &$s;
+            let split = $crate::split::SplitBorrow::new($token);
+            ($(unsafe {split.borrow_mut(&s.$field)},)+)
+        };
+    }
+}
+
+#[test]
+fn split_borrow_test() {
+    use crate::TokenBuilder;
+
+    struct Pair<const ID: usize> {
+        a: Cell<u32, ID>,
+        b: Cell<u32, ID>,
+    }
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let pair = Pair { a: Cell::new(1), b: Cell::new(2) };
+
+    split_borrow!(let {a, b} = pair, &mut token);
+    std::mem::swap(a, b);
+
+    assert_eq!(*pair.a.borrow(&token), 2);
+    assert_eq!(*pair.b.borrow(&token), 1);
+}
@@ -0,0 +1,161 @@
+use std::marker::PhantomData;
+use std::mem::MaybeUninit;
+
+use crate::sync::{AtomicUsize, Ordering, UnsafeCell};
+
+use crate::tokens::TokenWith;
+
+/// A fixed-capacity single-producer single-consumer queue. Pushing requires the family's
+/// [Producer] and popping requires its [Consumer], so the type system already guarantees that only
+/// one thread is ever writing to either end and neither `push` nor `pop` needs a lock.
+///
+/// `Producer` and `Consumer` are deliberately *not* [TokenWith]s: two `TokenWith`s of the same ID
+/// would each be accepted by every [Cell](crate::cells::Cell) of that family.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, spsc::RingBuffer};
+/// let (token, _) = first().unwrap().token();
+/// let (ring, mut producer, mut consumer) = RingBuffer::new(token, 16);
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| {
+///         for i in 0..100 {
+///             while ring.push(&mut producer, i).is_err() {}
+///         }
+///     });
+///
+///     let mut received = 0;
+///     while received < 100 {
+///         if let Some(i) = ring.pop(&mut consumer) {
+///             assert_eq!(i, received);
+///             received += 1;
+///         }
+///     }
+/// });
+/// ```
+pub struct RingBuffer<T, const ID: usize> {
+    slots: Box<[UnsafeCell<MaybeUninit<T>>]>,
+    // Total number of values pushed, only written by the producer
+    head: AtomicUsize,
+    // Total number of values popped, only written by the consumer
+    tail: AtomicUsize,
+}
+
+/// The writing end of a [RingBuffer].
+pub struct Producer<const ID: usize> {
+    head: usize,
+    _marker: PhantomData<()>,
+}
+
+/// The reading end of a [RingBuffer].
+pub struct Consumer<const ID: usize> {
+    tail: usize,
+    _marker: PhantomData<()>,
+}
+
+// Safety: a slot is only ever accessed by the producer before `head` is published and by the
+// consumer after, so values are only moved between threads, never shared.
+unsafe impl<T: Send, const ID: usize> Send for RingBuffer<T, ID> {}
+unsafe impl<T: Send, const ID: usize> Sync for RingBuffer<T, ID> {}
+
+impl<T, const ID: usize> RingBuffer<T, ID> {
+    /// Creates a queue holding at most `capacity` values. The token is consumed to guarantee that
+    /// this is the only `RingBuffer` (and the only [Producer]/[Consumer] pair) of this family.
+    ///
+    /// # Panics
+    /// Panics if `capacity` is 0.
+    pub fn new<U>(_: TokenWith<U, ID>, capacity: usize) -> (Self, Producer<ID>, Consumer<ID>) {
+        assert!(capacity > 0, "RingBuffer capacity must be non-zero");
+
+        let ring = Self {
+            slots: (0..capacity).map(|_| UnsafeCell::new(MaybeUninit::uninit())).collect(),
+            head: AtomicUsize::new(0),
+            tail: AtomicUsize::new(0),
+        };
+
+        (ring,
+         Producer { head: 0, _marker: PhantomData },
+         Consumer { tail: 0, _marker: PhantomData })
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.slots.len()
+    }
+
+    /// Number of values currently waiting to be popped. Since the other end may be running
+    /// concurrently, this is only a snapshot.
+    pub fn len(&self) -> usize {
+        let tail = self.tail.load(Ordering::Acquire);
+        self.head.load(Ordering::Acquire).wrapping_sub(tail)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Appends a value, handing it back if the queue is full.
+    pub fn push(&self, producer: &mut Producer<ID>, value: T) -> Result<(), T> {
+        let head = producer.head;
+        if head.wrapping_sub(self.tail.load(Ordering::Acquire)) == self.capacity() {
+            return Err(value);
+        }
+
+        // Safety: the consumer never touches slots between `tail + len` and `head`
+        self.slots[head % self.capacity()].with_mut(|slot| unsafe {(*slot).write(value);});
+
+        producer.head = head.wrapping_add(1);
+        self.head.store(producer.head, Ordering::Release);
+        Ok(())
+    }
+
+    /// Removes the oldest value, if there is one.
+    pub fn pop(&self, consumer: &mut Consumer<ID>) -> Option<T> {
+        let tail = consumer.tail;
+        if tail == self.head.load(Ordering::Acquire) {
+            return None;
+        }
+
+        // Safety: the slot was initialized by `push` before `head` was published
+        let value = self.slots[tail % self.capacity()].with_mut(|slot| unsafe {(*slot).assume_init_read()});
+
+        consumer.tail = tail.wrapping_add(1);
+        self.tail.store(consumer.tail, Ordering::Release);
+        Some(value)
+    }
+}
+
+impl<T, const ID: usize> Drop for RingBuffer<T, ID> {
+    fn drop(&mut self) {
+        let head = self.head.load(Ordering::Relaxed);
+        let mut tail = self.tail.load(Ordering::Relaxed);
+
+        while tail != head {
+            self.slots[tail % self.slots.len()].with_mut(|slot| unsafe {(*slot).assume_init_drop()});
+            tail = tail.wrapping_add(1);
+        }
+    }
+}
+
+#[test]
+fn ring_buffer_test() {
+    use std::rc::Rc;
+    use crate::TokenBuilder;
+
+    let (token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let (ring, mut producer, mut consumer) = RingBuffer::new(token, 2);
+    let value = Rc::new(());
+
+    assert!(ring.push(&mut producer, value.clone()).is_ok());
+    assert!(ring.push(&mut producer, value.clone()).is_ok());
+    assert!(ring.push(&mut producer, value.clone()).is_err());
+    assert_eq!(ring.len(), 2);
+
+    assert!(ring.pop(&mut consumer).is_some());
+    assert!(ring.push(&mut producer, value.clone()).is_ok());
+    assert_eq!(Rc::strong_count(&value), 3);
+
+    // Remaining values are dropped along with the queue
+    drop(ring);
+    assert_eq!(Rc::strong_count(&value), 1);
+}
@@ -0,0 +1,144 @@
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// A table of the transitions a state machine allows, usually generated with [transitions!].
+///
+/// [transitions!]: crate::transitions
+pub trait Transitions {
+    /// Whether the machine may move from `from` to `to`.
+    fn allowed(from: &Self, to: &Self) -> bool;
+}
+
+/// A cell holding the current state of an enum state machine. States are replaced as a whole with
+/// [Self::transition], or checked against a [Transitions] table with [Self::try_transition],
+/// instead of matching on the state and assigning to it in place.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, state::StateCell};
+/// #[derive(Debug, PartialEq)]
+/// enum Conn { Idle, Connecting(u32), Open }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let conn = StateCell::new(Conn::Idle);
+///
+/// for _ in 0..3 {
+///     conn.transition(&mut token, |state| match state {
+///         Conn::Idle => Conn::Connecting(1),
+///         Conn::Connecting(3) => Conn::Open,
+///         Conn::Connecting(n) => Conn::Connecting(n + 1),
+///         Conn::Open => Conn::Open,
+///     });
+/// }
+///
+/// assert_eq!(conn.borrow(&token), &Conn::Connecting(3));
+/// ```
+pub struct StateCell<S, const ID: usize> {
+    // Only `None` while a transition is running, or after one panicked
+    state: Cell<Option<S>, ID>,
+}
+
+impl<S, const ID: usize> StateCell<S, ID> {
+    pub const fn new(state: S) -> Self {
+        Self { state: Cell::new(Some(state)) }
+    }
+
+    /// # Panics
+    /// Panics if a transition panicked, leaving the machine without a state.
+    pub fn into_inner(self) -> S {
+        self.state.into_inner().expect("StateCell poisoned by a panicking transition")
+    }
+
+    /// The current state.
+    ///
+    /// # Panics
+    /// Panics if a transition panicked, leaving the machine without a state.
+    pub fn borrow<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a S {
+        self.state.borrow(token).as_ref().expect("StateCell poisoned by a panicking transition")
+    }
+
+    /// Moves the machine to the state `f` returns, given the current one by value.
+    ///
+    /// # Panics
+    /// Panics if a transition panicked, leaving the machine without a state. That includes `f`
+    /// itself: if it panics, every later access panics too.
+    pub fn transition<U>(&self, token: &mut TokenWith<U, ID>, f: impl FnOnce(S) -> S) {
+        let state = self.state.borrow_mut(token);
+        let current = state.take().expect("StateCell poisoned by a panicking transition");
+        *state = Some(f(current));
+    }
+
+    /// Moves the machine to `next` if its [Transitions] table allows it, returning the old state.
+    /// Otherwise the machine is left untouched and `next` is given back.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, transitions, state::StateCell};
+    /// #[derive(Debug, PartialEq)]
+    /// enum Light { Red, Green, Yellow }
+    ///
+    /// transitions! {
+    ///     Light {
+    ///         Light::Red => Light::Green,
+    ///         Light::Green => Light::Yellow,
+    ///         Light::Yellow => Light::Red,
+    ///     }
+    /// }
+    ///
+    /// let (mut token, _) = first().unwrap().token();
+    /// let light = StateCell::new(Light::Red);
+    ///
+    /// assert_eq!(light.try_transition(&mut token, Light::Yellow), Err(Light::Yellow));
+    /// assert_eq!(light.try_transition(&mut token, Light::Green), Ok(Light::Red));
+    /// assert_eq!(light.borrow(&token), &Light::Green);
+    /// ```
+    ///
+    /// # Panics
+    /// Panics if a transition panicked, leaving the machine without a state.
+    pub fn try_transition<U>(&self, token: &mut TokenWith<U, ID>, next: S) -> Result<S, S> where S: Transitions {
+        let state = self.state.borrow_mut(token).as_mut().expect("StateCell poisoned by a panicking transition");
+        if S::allowed(state, &next) {
+            Ok(std::mem::replace(state, next))
+        } else {
+            Err(next)
+        }
+    }
+
+    /// Whether a transition panicked, leaving the machine without a state.
+    pub fn is_poisoned<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.state.borrow(token).is_none()
+    }
+}
+
+/// Implements [Transitions](crate::state::Transitions) for a state type from a table of allowed
+/// `from => to` pairs. Each side is a pattern, so variants with data can be matched with `..`.
+/// See [StateCell::try_transition](crate::state::StateCell::try_transition) for an example.
+#[macro_export]
+macro_rules! transitions {
+    ($state:ty { $($from:pat => $to:pat),* $(,)? }) => {
+        impl $crate::state::Transitions for $state {
+            fn allowed(from: &Self, to: &Self) -> bool {
+                #[allow(unreachable_patterns)]
+                match (from, to) {
+                    $(($from, $to) => true,)*
+                    _ => false,
+                }
+            }
+        }
+    }
+}
+
+#[test]
+fn state_cell_poison_test() {
+    use crate::TokenBuilder;
+
+    let (mut token, _) = unsafe {TokenBuilder::<1000>::new()}.token();
+    let state = StateCell::new(0);
+
+    let panicked = std::panic::catch_unwind(std::panic::AssertUnwindSafe(|| {
+        state.transition(&mut token, |_| panic!("bad transition"));
+    }));
+
+    assert!(panicked.is_err());
+    assert!(state.is_poisoned(&token));
+}
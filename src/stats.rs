@@ -0,0 +1,129 @@
+//! Family-wide statistics for leak hunting and capacity planning, read with [stats()].
+//!
+//! Counting only happens when the `stats` feature is enabled. Without it, nothing is recorded and
+//! [stats()] reports no families. Plain [Cell](crate::Cell)s and tokens are built in `const fn`s,
+//! which can't record anything, so only the crate's arenas are counted.
+
+use std::collections::BTreeMap;
+use std::panic::Location;
+
+/// The structures currently alive in one family.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct FamilyStats {
+    /// [Arena](crate::arena::Arena)s that have allocated at least one slot.
+    pub arenas: usize,
+    /// Slots across all of those arenas, whether occupied or vacant.
+    pub arena_slots: usize,
+    /// Live entries across all of those arenas.
+    pub arena_entries: usize,
+}
+
+/// A snapshot of every family's statistics, taken with [stats()].
+#[derive(Clone, Debug, Default)]
+pub struct Stats {
+    /// Where [first()](crate::first) claimed the first `TokenBuilder`, if it has been.
+    pub first_claimed_at: Option<&'static Location<'static>>,
+    /// Every family that has had something counted, keyed by ID.
+    pub families: BTreeMap<usize, FamilyStats>,
+}
+
+/// Takes a snapshot of every family's statistics.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, arena::Arena};
+/// let _builder = first().unwrap();
+/// let mut arena = Arena::<_, 0>::new();
+/// let a = arena.insert("a");
+/// arena.insert("b");
+/// arena.remove(a);
+///
+/// let stats = frankencell::stats();
+/// assert!(stats.first_claimed_at.is_some());
+///
+/// // Empty without the `stats` feature
+/// if let Some(family) = stats.families.get(&0) {
+///     assert_eq!((family.arenas, family.arena_slots, family.arena_entries), (1, 2, 1));
+/// }
+/// ```
+pub fn stats() -> Stats {
+    Stats {
+        first_claimed_at: crate::claimed_at(),
+        families: counts::snapshot(),
+    }
+}
+
+pub(crate) use counts::ArenaCounts;
+
+#[cfg(feature = "stats")]
+mod counts {
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+
+    use super::FamilyStats;
+
+    static FAMILIES: Mutex<BTreeMap<usize, FamilyStats>> = Mutex::new(BTreeMap::new());
+
+    // Adds signed deltas to a family's arena counts
+    fn record_arena(id: usize, arenas: isize, slots: isize, entries: isize) {
+        // Counts are updated in one step, so a panic elsewhere can't leave them inconsistent
+        let mut families = FAMILIES.lock().unwrap_or_else(|e| e.into_inner());
+        let family = families.entry(id).or_default();
+        family.arenas = family.arenas.wrapping_add_signed(arenas);
+        family.arena_slots = family.arena_slots.wrapping_add_signed(slots);
+        family.arena_entries = family.arena_entries.wrapping_add_signed(entries);
+    }
+
+    pub(super) fn snapshot() -> BTreeMap<usize, FamilyStats> {
+        FAMILIES.lock().unwrap_or_else(|e| e.into_inner()).clone()
+    }
+
+    /// One arena's share of its family's counts, taken back out when the arena is dropped. Kept
+    /// in a field of its own so that `Arena` doesn't need a `Drop` impl, which would make the
+    /// borrow checker stricter about arenas of references.
+    pub(crate) struct ArenaCounts<const ID: usize> {
+        slots: usize,
+        entries: usize,
+    }
+
+    impl<const ID: usize> ArenaCounts<ID> {
+        pub(crate) const fn new() -> Self {
+            Self { slots: 0, entries: 0 }
+        }
+
+        pub(crate) fn record(&mut self, slots: usize, entries: isize) {
+            let arenas = (self.slots == 0 && slots > 0) as isize;
+            record_arena(ID, arenas, slots as isize, entries);
+            self.slots += slots;
+            self.entries = self.entries.wrapping_add_signed(entries);
+        }
+    }
+
+    impl<const ID: usize> Drop for ArenaCounts<ID> {
+        fn drop(&mut self) {
+            record_arena(ID, -((self.slots > 0) as isize), -(self.slots as isize), -(self.entries as isize));
+        }
+    }
+}
+
+#[cfg(not(feature = "stats"))]
+mod counts {
+    use std::collections::BTreeMap;
+
+    use super::FamilyStats;
+
+    pub(super) fn snapshot() -> BTreeMap<usize, FamilyStats> {
+        BTreeMap::new()
+    }
+
+    pub(crate) struct ArenaCounts<const ID: usize>;
+
+    impl<const ID: usize> ArenaCounts<ID> {
+        pub(crate) const fn new() -> Self {
+            Self
+        }
+
+        #[inline(always)]
+        pub(crate) fn record(&mut self, _: usize, _: isize) {}
+    }
+}
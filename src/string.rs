@@ -0,0 +1,96 @@
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// A `String` that can be read with a `&Token` and appended to with a `&mut Token`, for the
+/// common "many places hold a reference, a few occasionally write to it" pattern that usually
+/// ends up as a `RefCell<String>`.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, string::TokenString};
+/// let (mut token, _) = first().unwrap().token();
+/// let log = TokenString::new();
+///
+/// let writers = [&log, &log];
+/// for (i, w) in writers.iter().enumerate() {
+///     w.push_str(&mut token, &format!("writer {i}\n"));
+/// }
+///
+/// assert_eq!(log.as_str(&token), "writer 0\nwriter 1\n");
+/// ```
+#[derive(Default)]
+#[repr(transparent)]
+pub struct TokenString<const ID: usize> {
+    inner: Cell<String, ID>,
+}
+
+impl<const ID: usize> TokenString<ID> {
+    pub const fn new() -> Self {
+        Self {
+            inner: Cell::new(String::new()),
+        }
+    }
+
+    pub fn into_string(self) -> String {
+        self.inner.into_inner()
+    }
+
+    pub fn as_str<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a str {
+        self.inner.borrow(token)
+    }
+
+    /// Mutable access to the underlying `String`, for anything not covered by the methods below.
+    #[allow(clippy::mut_from_ref)]
+    pub fn as_mut_string<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut String {
+        self.inner.borrow_mut(token)
+    }
+
+    pub fn len<U>(&self, token: &TokenWith<U, ID>) -> usize {
+        self.as_str(token).len()
+    }
+
+    pub fn is_empty<U>(&self, token: &TokenWith<U, ID>) -> bool {
+        self.as_str(token).is_empty()
+    }
+
+    pub fn push<U>(&self, token: &mut TokenWith<U, ID>, c: char) {
+        self.as_mut_string(token).push(c)
+    }
+
+    pub fn push_str<U>(&self, token: &mut TokenWith<U, ID>, s: &str) {
+        self.as_mut_string(token).push_str(s)
+    }
+
+    /// See [String::insert]
+    pub fn insert<U>(&self, token: &mut TokenWith<U, ID>, idx: usize, c: char) {
+        self.as_mut_string(token).insert(idx, c)
+    }
+
+    /// See [String::insert_str]
+    pub fn insert_str<U>(&self, token: &mut TokenWith<U, ID>, idx: usize, s: &str) {
+        self.as_mut_string(token).insert_str(idx, s)
+    }
+
+    /// See [String::truncate]
+    pub fn truncate<U>(&self, token: &mut TokenWith<U, ID>, new_len: usize) {
+        self.as_mut_string(token).truncate(new_len)
+    }
+
+    pub fn clear<U>(&self, token: &mut TokenWith<U, ID>) {
+        self.as_mut_string(token).clear()
+    }
+}
+
+impl<const ID: usize> From<String> for TokenString<ID> {
+    fn from(s: String) -> Self {
+        Self {
+            inner: Cell::new(s),
+        }
+    }
+}
+
+impl<const ID: usize> From<&str> for TokenString<ID> {
+    fn from(s: &str) -> Self {
+        Self::from(String::from(s))
+    }
+}
@@ -0,0 +1,112 @@
+//! Synchronization primitives used internally, swapped for `loom`'s model-checked versions when
+//! built with `--cfg loom`.
+
+#[cfg(loom)]
+pub(crate) use loom::{cell::UnsafeCell, sync::atomic::{fence, AtomicPtr, AtomicUsize, Ordering}};
+
+// loom only switches threads at its own operations, so spin loops have to yield to it
+#[cfg(loom)]
+pub(crate) use loom::thread::yield_now as spin_loop;
+
+#[cfg(not(loom))]
+pub(crate) use std::hint::spin_loop;
+
+// The crate needs std, so this is only for std targets without native compare-and-swap, where
+// `portable-atomic` additionally needs to be told how to emulate it, e.g. with its
+// `critical-section` feature or `--cfg portable_atomic_unsafe_assume_single_core`.
+// `AtomicPtr` goes unused where `ClaimFlag` is a plain cell and `crossbeam-epoch` is off
+#[cfg(all(not(loom), feature = "portable-atomic"))]
+#[allow(unused_imports)]
+pub(crate) use portable_atomic::{fence, AtomicPtr, AtomicUsize, Ordering};
+
+#[cfg(all(not(loom), not(feature = "portable-atomic")))]
+#[allow(unused_imports)]
+pub(crate) use std::sync::atomic::{fence, AtomicPtr, AtomicUsize, Ordering};
+
+/// Atomics for `static` counters and flags. loom's types can't be created in a const context, and
+/// these are never part of a model, so they stay real atomics under loom.
+#[allow(unused_imports)]
+pub(crate) mod statics {
+    // `pub` so that `brand!` can reach `AtomicBool` through a re-export
+    #[cfg(feature = "portable-atomic")]
+    pub use portable_atomic::{AtomicBool, AtomicU32, AtomicUsize};
+
+    #[cfg(not(feature = "portable-atomic"))]
+    pub use std::sync::atomic::{AtomicBool, AtomicU32, AtomicUsize};
+}
+
+/// `std::cell::UnsafeCell` with `loom::cell::UnsafeCell`'s closure-based API.
+#[cfg(not(loom))]
+#[derive(Debug)]
+pub(crate) struct UnsafeCell<T>(std::cell::UnsafeCell<T>);
+
+#[cfg(not(loom))]
+impl<T> UnsafeCell<T> {
+    pub(crate) const fn new(t: T) -> Self {
+        Self(std::cell::UnsafeCell::new(t))
+    }
+
+    pub(crate) fn with_mut<R>(&self, f: impl FnOnce(*mut T) -> R) -> R {
+        f(self.0.get())
+    }
+}
+
+// The flag `first()` claims. With the `single-threaded` feature, on targets that can't spawn
+// threads (no pointer-sized atomics, or wasm without the `atomics` target feature), it's a plain
+// cell instead of an atomic.
+#[cfg(not(all(
+    not(loom),
+    feature = "single-threaded",
+    any(not(target_has_atomic = "ptr"), all(target_family = "wasm", not(target_feature = "atomics"))),
+)))]
+pub(crate) type ClaimFlag = AtomicPtr<std::panic::Location<'static>>;
+
+#[cfg(all(
+    not(loom),
+    feature = "single-threaded",
+    any(not(target_has_atomic = "ptr"), all(target_family = "wasm", not(target_feature = "atomics"))),
+))]
+pub(crate) use single_threaded::ClaimFlag;
+
+#[cfg(all(
+    not(loom),
+    feature = "single-threaded",
+    any(not(target_has_atomic = "ptr"), all(target_family = "wasm", not(target_feature = "atomics"))),
+))]
+mod single_threaded {
+    use std::panic::Location;
+
+    use super::Ordering;
+
+    /// `AtomicPtr<Location>`'s API over a plain `Cell`.
+    pub(crate) struct ClaimFlag(std::cell::Cell<*mut Location<'static>>);
+
+    // Safety: only compiled for targets where no other thread can exist
+    unsafe impl Sync for ClaimFlag {}
+
+    impl ClaimFlag {
+        pub(crate) const fn new(p: *mut Location<'static>) -> Self {
+            Self(std::cell::Cell::new(p))
+        }
+
+        pub(crate) fn compare_exchange(
+            &self,
+            current: *mut Location<'static>,
+            new: *mut Location<'static>,
+            _: Ordering,
+            _: Ordering,
+        ) -> Result<*mut Location<'static>, *mut Location<'static>> {
+            let old = self.0.get();
+            if old == current {
+                self.0.set(new);
+                Ok(old)
+            } else {
+                Err(old)
+            }
+        }
+
+        pub(crate) fn load(&self, _: Ordering) -> *mut Location<'static> {
+            self.0.get()
+        }
+    }
+}
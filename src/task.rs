@@ -0,0 +1,95 @@
+use std::cell::RefCell;
+use std::future::Future;
+
+use tokio::task::LocalKey;
+
+use crate::tokens::TokenWith;
+
+#[doc(hidden)]
+pub use tokio as __tokio;
+
+/// A token stored in tokio task-local storage, declared with [task_local_token!]. Code running
+/// inside [Self::scope] can borrow the token with [Self::with] and [Self::with_mut] without it
+/// being passed through every `async fn` in between.
+///
+/// The borrow only lasts for the (synchronous) closure, so it can't be held across an `.await`.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, Token, task_local_token};
+/// task_local_token! {
+///     static TOKEN: Token<0>;
+/// }
+///
+/// static HITS: Cell<u32, 0> = Cell::new(0);
+///
+/// async fn handle_request() {
+///     TOKEN.with_mut(|token| *HITS.borrow_mut(token) += 1);
+/// }
+///
+/// let (token, _) = first().unwrap().token();
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+///
+/// let ((), token) = runtime.block_on(TOKEN.scope(token, async {
+///     handle_request().await;
+///     handle_request().await;
+/// }));
+/// assert_eq!(HITS.borrow(&token), &2);
+/// ```
+///
+/// [task_local_token!]: crate::task_local_token
+pub struct TaskLocalToken<T: 'static> {
+    key: &'static LocalKey<RefCell<Option<T>>>,
+}
+
+impl<T: 'static> TaskLocalToken<T> {
+    #[doc(hidden)]
+    pub const fn __new(key: &'static LocalKey<RefCell<Option<T>>>) -> Self {
+        Self { key }
+    }
+}
+
+impl<U: 'static, const ID: usize> TaskLocalToken<TokenWith<U, ID>> {
+    /// Runs `f` with `token` installed, then gives the token back along with `f`'s output.
+    pub async fn scope<F: Future>(&'static self, token: TokenWith<U, ID>, f: F) -> (F::Output, TokenWith<U, ID>) {
+        self.key.scope(RefCell::new(Some(token)), async {
+            let output = f.await;
+            let token = self.key.with(|token| token.borrow_mut().take()).expect("task-local token taken");
+            (output, token)
+        }).await
+    }
+
+    /// # Panics
+    /// Panics if called outside of [Self::scope], or from inside [Self::with_mut].
+    pub fn with<R>(&'static self, f: impl FnOnce(&TokenWith<U, ID>) -> R) -> R {
+        self.key.with(|token| f(token.borrow().as_ref().expect("task-local token taken")))
+    }
+
+    /// # Panics
+    /// Panics if called outside of [Self::scope], or from inside [Self::with] or `with_mut`.
+    pub fn with_mut<R>(&'static self, f: impl FnOnce(&mut TokenWith<U, ID>) -> R) -> R {
+        self.key.with(|token| f(token.borrow_mut().as_mut().expect("task-local token taken")))
+    }
+}
+
+/// Declares a [TaskLocalToken](crate::task::TaskLocalToken).
+///
+/// ```rust
+/// # use frankencell::{Token, task_local_token};
+/// task_local_token! {
+///     pub static TOKEN: Token<0>;
+/// }
+/// ```
+#[macro_export]
+macro_rules! task_local_token {
+    ($(#[$attr:meta])* $vis:vis static $name:ident: $t:ty;) => {
+        $(#[$attr])*
+        $vis static $name: $crate::task::TaskLocalToken<$t> = {
+            $crate::task::__tokio::task_local! {
+                static KEY: ::std::cell::RefCell<::std::option::Option<$t>>;
+            }
+
+            $crate::task::TaskLocalToken::__new(&KEY)
+        };
+    }
+}
@@ -1,5 +1,7 @@
+use std::fmt::Debug;
 use std::marker::PhantomData;
 
+use crate::builder::TokenBuilder;
 use crate::cells::Cell;
 
 /// A generic token that can store any data. See examples/arena.rs for an example of how this could
@@ -12,7 +14,7 @@ pub struct TokenWith<T, const ID: usize> (
 impl<T, const ID: usize> TokenWith<T, ID> {
     /// Creates a new token with this ID.
     ///
-    /// # Safety:
+    /// # Safety
     /// Because tokens represent access (mutable or immutable) to a memory location, creating >1
     /// tokens is equivalent to creating >1 mutable references to data.
     pub const unsafe fn new(t: T) -> Self {
@@ -22,8 +24,70 @@ impl<T, const ID: usize> TokenWith<T, ID> {
     pub const fn cell(&self, t: T) -> Cell<T, ID> {
         Cell::new(t)
     }
+
+    /// The ID of this token's family.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::first;
+    /// let (token, next) = first().unwrap().token_with("payload");
+    ///
+    /// assert_eq!(token.id(), 0);
+    /// assert_eq!(next.id(), 1);
+    /// assert_eq!(format!("{:?}", token), r#"TokenWith<0>("payload")"#);
+    /// ```
+    pub const fn id(&self) -> usize {
+        ID
+    }
+
+    /// Lends this token to `f` for the duration of the call. The reborrow `f` receives is only
+    /// valid inside the closure, so library code handed a token this way can't stash it (or
+    /// anything borrowed through it) into long-lived state.
+    ///
+    /// # Example
+    /// ```rust
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let log = Cell::new(Vec::new());
+    ///
+    /// let len = token.scope(|t| {
+    ///     log.borrow_mut(t).push("scoped");
+    ///     log.borrow(t).len()
+    /// });
+    /// assert_eq!(len, 1);
+    /// ```
+    ///
+    /// Nothing borrowed from the scoped token can escape:
+    /// ```compile_fail
+    /// # use frankencell::{first, Cell};
+    /// let (mut token, _) = first().unwrap().token();
+    /// let log = Cell::new(Vec::<&str>::new());
+    ///
+    /// let escaped = token.scope(|t| log.borrow_mut(t));
+    /// ```
+    pub fn scope<R>(&mut self, f: impl for<'s> FnOnce(&'s mut Self) -> R) -> R {
+        f(self)
+    }
 }
 
+impl<T: Debug, const ID: usize> Debug for TokenWith<T, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple(&format!("TokenWith<{}>", ID)).field(&self.0).finish()
+    }
+}
+
+#[cfg(feature = "defmt")]
+impl<T: defmt::Format, const ID: usize> defmt::Format for TokenWith<T, ID> {
+    fn format(&self, f: defmt::Formatter) {
+        defmt::write!(f, "TokenWith<{}>({})", ID, self.0)
+    }
+}
+
+// Behind a module, since the unstable syntax is rejected before `cfg`s are applied
+#[cfg(feature = "no-self-token")]
+mod no_self_token;
+#[cfg(feature = "no-self-token")]
+pub use no_self_token::NoSelfToken;
 
 /// A Token that represents access to one or more memory locations, each containing the same or
 /// different data types.
@@ -31,3 +95,55 @@ impl<T, const ID: usize> TokenWith<T, ID> {
 /// Currently, this crate only provides [Cell](crate::cells::Cell), but you may create your own
 /// ownership primitives. See examples/arena.rs for an example.
 pub type Token<const ID: usize> = TokenWith<(), ID>;
+
+/// Relates a family-tagged type to the same type in the next family, `ID + 1`, so that downstream
+/// code can name "this family and the next one" without needing `generic_const_exprs` itself.
+///
+/// Implemented for families 0 through 63. Code generic over `const ID: usize` can ask for it
+/// with a `Cell<T, ID>: Succ` bound.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell, Succ};
+/// // One stage's output is owned by the next stage's family
+/// struct Stage<C: Succ> {
+///     input: C,
+///     output: C::Next,
+/// }
+///
+/// let (a, next) = first().unwrap().token();
+/// let (b, _) = next.token();
+///
+/// let stage: Stage<Cell<u32, 0>> = Stage { input: Cell::new(1), output: Cell::new(2) };
+/// assert_eq!(stage.input.borrow(&a) + stage.output.borrow(&b), 3);
+/// ```
+pub trait Succ {
+    type Next;
+}
+
+// Spelled out for each family, since an associated type computed with `{ID + 1}` can't be
+// normalized in crates without `generic_const_exprs`
+macro_rules! impl_succ {
+    ($id:literal, $next:literal $(, $rest:literal)*) => {
+        impl Succ for TokenBuilder<$id> {
+            type Next = TokenBuilder<$next>;
+        }
+
+        impl<U> Succ for TokenWith<U, $id> {
+            type Next = TokenWith<U, $next>;
+        }
+
+        impl<T> Succ for Cell<T, $id> {
+            type Next = Cell<T, $next>;
+        }
+
+        impl_succ!($next $(, $rest)*);
+    };
+    ($last:literal) => {};
+}
+
+impl_succ!(
+    0, 1, 2, 3, 4, 5, 6, 7, 8, 9, 10, 11, 12, 13, 14, 15, 16, 17, 18, 19, 20, 21, 22, 23, 24, 25,
+    26, 27, 28, 29, 30, 31, 32, 33, 34, 35, 36, 37, 38, 39, 40, 41, 42, 43, 44, 45, 46, 47, 48, 49,
+    50, 51, 52, 53, 54, 55, 56, 57, 58, 59, 60, 61, 62, 63, 64
+);
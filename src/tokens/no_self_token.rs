@@ -0,0 +1,46 @@
+use super::TokenWith;
+
+/// Implemented for every type that doesn't contain a token at any depth, except trait objects
+/// (see below). Cells made with [Cell::new_checked] require it, which rules out storing a family's
+/// token inside one of its own cells: the token could then only be taken out with itself, so the
+/// family would be locked for good.
+///
+/// The compiler can't compare family IDs here, so tokens of every family are rejected, not just
+/// the cell's own. Use [Cell::new] to store another family's token.
+///
+/// Like any auto trait, it isn't implemented for trait objects unless they name it, since the
+/// compiler can't see what's behind them: `Box<dyn Any>` is rejected, while
+/// `Box<dyn Any + NoSelfToken>` is accepted.
+///
+/// Needs the `no-self-token` feature, which uses the unstable `auto_traits` and `negative_impls`.
+///
+/// [Cell::new_checked]: crate::Cell::new_checked
+/// [Cell::new]: crate::Cell::new
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, Cell};
+/// let (token, _) = first().unwrap().token();
+/// let names = Cell::new_checked(vec!["a"]);
+/// assert_eq!(names.borrow(&token).len(), 1);
+///
+/// use std::any::Any;
+/// use frankencell::tokens::NoSelfToken;
+/// let opaque = Cell::new_checked(Box::new(1) as Box<dyn Any + NoSelfToken>);
+/// let value: &dyn Any = &**opaque.borrow(&token);
+/// assert!(value.is::<i32>());
+/// ```
+///
+/// ```compile_fail
+/// # use frankencell::{first, Cell};
+/// let (token, _) = first().unwrap().token();
+/// let bricked = Cell::<_, 0>::new_checked(Some(token));
+/// ```
+///
+/// ```compile_fail
+/// # use frankencell::Cell;
+/// let opaque = Cell::<_, 0>::new_checked(Box::new(1) as Box<dyn std::any::Any>);
+/// ```
+pub auto trait NoSelfToken {}
+
+impl<T, const ID: usize> !NoSelfToken for TokenWith<T, ID> {}
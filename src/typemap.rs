@@ -0,0 +1,77 @@
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+/// A map holding at most one value of each type, whose values are borrowed with the family's
+/// token. Useful for plugin registries and per-context singletons, where the map is built once and
+/// then read or updated everywhere the token is available.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, typemap::TokenTypeMap};
+/// struct Config { verbose: bool }
+///
+/// let (mut token, _) = first().unwrap().token();
+/// let mut resources = TokenTypeMap::new();
+/// resources.insert(Config { verbose: false });
+/// resources.insert(0u64);
+///
+/// resources.get_mut::<Config>(&mut token).unwrap().verbose = true;
+/// *resources.get_mut::<u64>(&mut token).unwrap() += 1;
+///
+/// assert!(resources.get::<Config>(&token).unwrap().verbose);
+/// assert_eq!(resources.get::<u64>(&token), Some(&1));
+/// assert_eq!(resources.get::<String>(&token), None);
+/// ```
+pub struct TokenTypeMap<const ID: usize> {
+    values: HashMap<TypeId, Cell<Box<dyn Any>, ID>>,
+}
+
+impl<const ID: usize> Default for TokenTypeMap<ID> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const ID: usize> TokenTypeMap<ID> {
+    pub fn new() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+
+    /// The number of types with a value.
+    pub fn len(&self) -> usize {
+        self.values.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    /// Stores `value` as the map's `T`, returning the one it replaced.
+    pub fn insert<T: Any>(&mut self, value: T) -> Option<T> {
+        let old = self.values.insert(TypeId::of::<T>(), Cell::new(Box::new(value)))?;
+        old.into_inner().downcast().ok().map(|b| *b)
+    }
+
+    pub fn remove<T: Any>(&mut self) -> Option<T> {
+        let old = self.values.remove(&TypeId::of::<T>())?;
+        old.into_inner().downcast().ok().map(|b| *b)
+    }
+
+    pub fn contains<T: Any>(&self) -> bool {
+        self.values.contains_key(&TypeId::of::<T>())
+    }
+
+    pub fn get<'a, T: Any>(&'a self, token: &'a TokenWith<impl Sized, ID>) -> Option<&'a T> {
+        self.values.get(&TypeId::of::<T>())?.downcast_borrow(token)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn get_mut<'a, T: Any>(&'a self, token: &'a mut TokenWith<impl Sized, ID>) -> Option<&'a mut T> {
+        self.values.get(&TypeId::of::<T>())?.downcast_borrow_mut(token)
+    }
+}
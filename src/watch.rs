@@ -0,0 +1,134 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Mutex;
+use std::task::{Context, Poll, Waker};
+
+use crate::cells::Cell;
+use crate::sync::{AtomicUsize, Ordering};
+use crate::tokens::TokenWith;
+
+/// A cell that async code can wait on, similar to a watch channel. Writers mutate it through the
+/// family's token as usual and then call [Self::notify]; each [Watcher] created with
+/// [Self::subscribe] can `await` [Watcher::changed] and then read the new value.
+///
+/// Notifications aren't queued: a watcher that misses several of them only sees the latest
+/// value, once.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, watch::Watched};
+/// let (mut token, _) = first().unwrap().token();
+/// let status = Watched::new("starting");
+/// let mut watcher = status.subscribe();
+/// let runtime = tokio::runtime::Builder::new_current_thread().build().unwrap();
+///
+/// std::thread::scope(|s| {
+///     s.spawn(|| status.modify(&mut token, |status| *status = "ready"));
+///     runtime.block_on(watcher.changed());
+/// });
+///
+/// assert_eq!(watcher.borrow(&token), &"ready");
+/// ```
+pub struct Watched<T, const ID: usize> {
+    cell: Cell<T, ID>,
+    version: AtomicUsize,
+    wakers: Mutex<Vec<Waker>>,
+}
+
+impl<T, const ID: usize> Watched<T, ID> {
+    pub fn new(value: T) -> Self {
+        Self {
+            cell: Cell::new(value),
+            version: AtomicUsize::new(0),
+            wakers: Mutex::new(Vec::new()),
+        }
+    }
+
+    pub fn into_inner(self) -> T {
+        self.cell.into_inner()
+    }
+
+    pub fn borrow<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.cell.borrow(token)
+    }
+
+    /// Mutably borrows the value without notifying watchers; call [Self::notify] afterwards.
+    #[allow(clippy::mut_from_ref)]
+    pub fn borrow_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        self.cell.borrow_mut(token)
+    }
+
+    /// Calls `f` on the value, then notifies every watcher.
+    pub fn modify<U, R>(&self, token: &mut TokenWith<U, ID>, f: impl FnOnce(&mut T) -> R) -> R {
+        let output = f(self.cell.borrow_mut(token));
+        self.notify();
+        output
+    }
+
+    /// Wakes every watcher waiting in [Watcher::changed].
+    pub fn notify(&self) {
+        self.version.fetch_add(1, Ordering::Release);
+        for waker in self.wakers.lock().unwrap_or_else(|e| e.into_inner()).drain(..) {
+            waker.wake();
+        }
+    }
+
+    /// Creates a watcher that will see every notification from now on.
+    pub fn subscribe(&self) -> Watcher<'_, T, ID> {
+        Watcher {
+            watched: self,
+            seen: self.version.load(Ordering::Acquire),
+        }
+    }
+}
+
+/// A subscription to a [Watched] cell, created by [Watched::subscribe].
+pub struct Watcher<'a, T, const ID: usize> {
+    watched: &'a Watched<T, ID>,
+    seen: usize,
+}
+
+impl<'a, T, const ID: usize> Watcher<'a, T, ID> {
+    /// Whether there has been a notification this watcher hasn't seen yet.
+    pub fn has_changed(&self) -> bool {
+        self.watched.version.load(Ordering::Acquire) != self.seen
+    }
+
+    /// Waits for the next notification this watcher hasn't seen yet. Returns immediately if one
+    /// has already happened.
+    pub fn changed(&mut self) -> Changed<'_, 'a, T, ID> {
+        Changed { watcher: self }
+    }
+
+    pub fn borrow<'b, U>(&'b self, token: &'b TokenWith<U, ID>) -> &'b T {
+        self.watched.borrow(token)
+    }
+}
+
+/// Future returned by [Watcher::changed].
+pub struct Changed<'w, 'a, T, const ID: usize> {
+    watcher: &'w mut Watcher<'a, T, ID>,
+}
+
+impl<T, const ID: usize> Future for Changed<'_, '_, T, ID> {
+    type Output = ();
+
+    fn poll(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<()> {
+        let watcher = &mut *self.get_mut().watcher;
+        let watched = watcher.watched;
+
+        let mut wakers = watched.wakers.lock().unwrap_or_else(|e| e.into_inner());
+        // Checked while holding the lock, so a `notify` can't slip in between the check and
+        // registering the waker
+        let version = watched.version.load(Ordering::Acquire);
+        if version != watcher.seen {
+            watcher.seen = version;
+            return Poll::Ready(());
+        }
+
+        if !wakers.iter().any(|w| w.will_wake(cx.waker())) {
+            wakers.push(cx.waker().clone());
+        }
+        Poll::Pending
+    }
+}
@@ -0,0 +1,72 @@
+//! Support for wiping sensitive data held in cells, with the `zeroize` feature.
+
+use std::fmt::Debug;
+
+use ::zeroize::{Zeroize, ZeroizeOnDrop};
+
+use crate::cells::Cell;
+use crate::tokens::TokenWith;
+
+impl<T: Zeroize, const ID: usize> Zeroize for Cell<T, ID> {
+    fn zeroize(&mut self) {
+        self.get_mut().zeroize()
+    }
+}
+
+impl<T: Zeroize, const ID: usize> Cell<T, ID> {
+    /// Wipes the value through a shared reference to the cell, with the family's token.
+    pub fn zeroize_with<U>(&self, token: &mut TokenWith<U, ID>) {
+        self.borrow_mut(token).zeroize()
+    }
+}
+
+/// A cell for keys, passwords and other secrets. Its value is wiped when it's dropped, and its
+/// [Debug] output never shows it.
+///
+/// # Example
+/// ```rust
+/// # use frankencell::{first, zeroize::SecretCell};
+/// let (mut token, _) = first().unwrap().token();
+/// let key = SecretCell::new(*b"hunter2!");
+///
+/// key.borrow_mut(&mut token)[7] = b'?';
+/// assert_eq!(key.borrow(&token), b"hunter2?");
+/// assert_eq!(format!("{key:?}"), "SecretCell<[u8; 8], 0>(<redacted>)");
+/// ```
+#[repr(transparent)]
+pub struct SecretCell<T: Zeroize, const ID: usize> {
+    inner: Cell<T, ID>,
+}
+
+impl<T: Zeroize, const ID: usize> SecretCell<T, ID> {
+    pub const fn new(t: T) -> Self {
+        Self { inner: Cell::new(t) }
+    }
+
+    pub fn borrow<'a, U>(&'a self, token: &'a TokenWith<U, ID>) -> &'a T {
+        self.inner.borrow(token)
+    }
+
+    #[allow(clippy::mut_from_ref)]
+    pub fn borrow_mut<'a, U>(&'a self, token: &'a mut TokenWith<U, ID>) -> &'a mut T {
+        self.inner.borrow_mut(token)
+    }
+
+    pub fn get_mut(&mut self) -> &mut T {
+        self.inner.get_mut()
+    }
+}
+
+impl<T: Zeroize, const ID: usize> Drop for SecretCell<T, ID> {
+    fn drop(&mut self) {
+        self.inner.zeroize()
+    }
+}
+
+impl<T: Zeroize, const ID: usize> ZeroizeOnDrop for SecretCell<T, ID> {}
+
+impl<T: Zeroize, const ID: usize> Debug for SecretCell<T, ID> {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "SecretCell<{}, {}>(<redacted>)", std::any::type_name::<T>(), ID)
+    }
+}
@@ -0,0 +1,75 @@
+//! Model checks for the crate's concurrent internals. Run with:
+//!
+//! ```text
+//! RUSTFLAGS="--cfg loom" cargo test --test loom --release
+//! ```
+#![cfg(loom)]
+
+use frankencell::seqlock::SeqCell;
+use frankencell::spsc::RingBuffer;
+use frankencell::{try_first, TokenBuilder};
+use loom::sync::Arc;
+use loom::thread;
+
+#[test]
+fn first_is_claimed_once() {
+    loom::model(|| {
+        let other = thread::spawn(|| try_first().is_ok());
+        let here = try_first().is_ok();
+
+        assert!(here ^ other.join().unwrap());
+    });
+}
+
+#[test]
+fn ring_buffer_transfers_in_order() {
+    loom::model(|| {
+        let (token, _) = unsafe {TokenBuilder::<0>::new()}.token();
+        let (ring, mut producer, mut consumer) = RingBuffer::new(token, 2);
+        let ring = Arc::new(ring);
+
+        let pusher = {
+            let ring = ring.clone();
+            thread::spawn(move || {
+                for i in 0..3 {
+                    while ring.push(&mut producer, i).is_err() {
+                        thread::yield_now();
+                    }
+                }
+            })
+        };
+
+        let mut received = Vec::new();
+        while received.len() < 3 {
+            match ring.pop(&mut consumer) {
+                Some(i) => received.push(i),
+                None => thread::yield_now(),
+            }
+        }
+
+        pusher.join().unwrap();
+        assert_eq!(received, [0, 1, 2]);
+    });
+}
+
+#[test]
+fn seq_cell_reads_are_never_torn() {
+    loom::model(|| {
+        let (mut token, _) = unsafe {TokenBuilder::<0>::new()}.token();
+        let cell = Arc::new(SeqCell::<_, 0>::new((0u8, 0u8)));
+
+        let reader = {
+            let cell = cell.clone();
+            thread::spawn(move || {
+                let (a, b) = cell.read();
+                assert_eq!(a, b);
+            })
+        };
+
+        cell.set(&mut token, (1, 1));
+        cell.set(&mut token, (2, 2));
+
+        reader.join().unwrap();
+        assert_eq!(cell.get(&token), (2, 2));
+    });
+}